@@ -2,9 +2,11 @@ use chrono::Local;
 use clap::Parser;
 use rand::Rng;
 use termitype::{
-    constants::db_file,
+    config::ErrorPolicy,
+    constants::{DEFAULT_LAYOUT, db_file},
     db::{Db, LeaderboardResult},
     error::AppResult,
+    tracker::CharStats,
 };
 
 #[derive(Parser)]
@@ -65,12 +67,17 @@ fn generate_dummy_result() -> LeaderboardResult {
     let wpm = rng.random_range(20..=350) as u16;
     let raw_wpm = wpm + rng.random_range(10..=25);
     let accuracy = rng.random_range(30..=100) as u16;
+    let keystroke_accuracy = accuracy.saturating_sub(rng.random_range(0..=10));
     let consistency = rng.random_range(30..=100) as u16;
     let error_count = rng.random_range(0..=100) as u32;
 
     let numbers = rng.random_bool(0.3);
     let symbols = rng.random_bool(0.2);
     let punctuation = rng.random_bool(0.2);
+    let policies = ErrorPolicy::ALL;
+    let error_policy = policies[rng.random_range(0..policies.len())]
+        .label()
+        .to_string();
 
     let created_at = Local::now() - chrono::Duration::days(rng.random_range(0..30));
 
@@ -82,11 +89,22 @@ fn generate_dummy_result() -> LeaderboardResult {
         wpm,
         raw_wpm,
         accuracy,
+        keystroke_accuracy,
         consistency,
         error_count,
         numbers,
         symbols,
         punctuation,
+        error_policy,
         created_at,
+        valid: true,
+        char_stats: CharStats::default(),
+        lazy: false,
+        layout: DEFAULT_LAYOUT.to_string(),
+        funbox: String::new(),
+        recording: None,
+        key_stats: None,
+        ngram_stats: None,
+        missed_words: None,
     }
 }
@@ -1,6 +1,6 @@
 use crate::{
     app::App,
-    config::Setting,
+    config::{Difficulty, ErrorPolicy, PaceCaret, QuoteLength, Setting},
     error::AppError,
     leaderboard::{LeaderboardMotion, SortColumn},
     menu::{MenuContext, MenuMotion},
@@ -16,9 +16,15 @@ pub enum Action {
     Restart,
     Start,
     Redo,
+    GhostRace,
+    PracticeNgrams,
+    PracticeMissedWords,
+    PracticeMistakes,
 
     Input(char),
     Backspace,
+    DeleteWord,
+    Finish,
 
     MenuNav(MenuMotion),
     MenuOpen(MenuContext),
@@ -45,12 +51,21 @@ pub enum Action {
     LeaderboardToggle,
     LeaderboardSort(SortColumn),
     LeaderboardNav(LeaderboardMotion),
+    LeaderboardReplay,
+    LeaderboardKeyStats,
+    LeaderboardPracticeNgrams,
+    LeaderboardToggleInvalid,
 
     Toggle(Setting),
     Enable(Setting),
     Disable(Setting),
 
     SetLineCount(u8),
+    SetErrorPolicy(ErrorPolicy),
+    SetDifficulty(Difficulty),
+    SetPaceCaret(PaceCaret),
+    SetPracticeRepetitions(u8),
+    SetMemory(u8),
     SetTheme(String),
     SetCursorVariant(CursorVariant),
     SetPickerVariant(PickerVariant),
@@ -58,8 +73,12 @@ pub enum Action {
 
     SetTime(u16),
     SetWords(u16),
+    SetQuote(QuoteLength),
+    SetZen,
+    SetCode,
     SetAsciiArt(String),
     SetLanguage(String),
+    SetLayout(String),
 
     RandomizeTheme,
     CyclePreviousArt,
@@ -72,8 +91,14 @@ pub fn handle_action(app: &mut App, action: Action) -> Result<(), AppError> {
         Action::Quit => app.quit(),
         Action::Restart => app.restart(),
         Action::Redo => app.redo(),
+        Action::GhostRace => app.start_ghost_race(),
+        Action::PracticeNgrams => app.handler.handle_practice_ngrams(&mut *app),
+        Action::PracticeMissedWords => app.practice_missed_words(),
+        Action::PracticeMistakes => app.practice_mistakes(),
         Action::Input(c) => app.handler.handle_input(&mut *app, c),
         Action::Backspace => app.handler.handle_backspace(&mut *app),
+        Action::DeleteWord => app.handler.handle_delete_word(&mut *app),
+        Action::Finish => app.handler.handle_finish(&mut *app),
         Action::MenuNav(motion) => app.handler.handle_menu_navigate(&mut *app, motion),
         Action::MenuOpen(ctx) => app.handler.handle_menu_open(&mut *app, ctx),
         Action::MenuShortcut(shortcut) => app.handler.handle_menu_shortcut(&mut *app, shortcut),
@@ -96,17 +121,38 @@ pub fn handle_action(app: &mut App, action: Action) -> Result<(), AppError> {
         Action::LeaderboardToggle => app.handler.handle_leaderboard_toggle(&mut *app),
         Action::LeaderboardSort(col) => app.handler.handle_leaderboard_sort(&mut *app, col),
         Action::LeaderboardNav(motion) => app.handler.handle_leaderboard_nav(&mut *app, motion),
+        Action::LeaderboardReplay => app.handler.handle_leaderboard_replay(&mut *app),
+        Action::LeaderboardKeyStats => app.handler.handle_leaderboard_key_stats(&mut *app),
+        Action::LeaderboardPracticeNgrams => {
+            app.handler.handle_leaderboard_practice_ngrams(&mut *app)
+        }
+        Action::LeaderboardToggleInvalid => {
+            app.handler.handle_leaderboard_toggle_invalid(&mut *app)
+        }
         Action::Toggle(setting) => app.handler.handle_toggle_setting(&mut *app, setting),
         Action::Enable(setting) => app.handler.handle_enable_setting(&mut *app, setting),
         Action::Disable(setting) => app.handler.handle_disable_setting(&mut *app, setting),
         Action::SetLineCount(count) => app.handler.handle_set_line_count(&mut *app, count),
+        Action::SetErrorPolicy(policy) => app.handler.handle_set_error_policy(&mut *app, policy),
+        Action::SetDifficulty(difficulty) => {
+            app.handler.handle_set_difficulty(&mut *app, difficulty)
+        }
+        Action::SetPaceCaret(pace) => app.handler.handle_set_pace_caret(&mut *app, pace),
+        Action::SetPracticeRepetitions(repetitions) => app
+            .handler
+            .handle_set_practice_repetitions(&mut *app, repetitions),
+        Action::SetMemory(secs) => app.handler.handle_set_memory(&mut *app, secs),
         Action::SetTheme(name) => app.handler.handle_change_theme(&mut *app, name),
         Action::SetCursorVariant(variant) => app.handler.handle_set_cursor(&mut *app, variant),
         Action::SetPickerVariant(variant) => app.handler.handle_set_picker(&mut *app, variant),
         Action::SetResultVariant(variant) => app.handler.handle_set_result(&mut *app, variant),
         Action::SetTime(secs) => app.handler.handle_set_time(&mut *app, secs as usize),
         Action::SetWords(count) => app.handler.handle_set_words(&mut *app, count as usize),
+        Action::SetQuote(length) => app.handler.handle_set_quote(&mut *app, length),
+        Action::SetZen => app.handler.handle_set_zen(&mut *app),
+        Action::SetCode => app.handler.handle_set_code(&mut *app),
         Action::SetLanguage(lang) => app.handler.handle_set_language(&mut *app, lang),
+        Action::SetLayout(layout) => app.handler.handle_set_layout(&mut *app, layout),
         Action::SetAsciiArt(art) => app.handler.handle_set_ascii_art(&mut *app, art),
         Action::RandomizeTheme => app.handler.handle_randomize_theme(&mut *app),
         Action::CycleNextArt => app.handler.handle_cycle_prev_art(&mut *app),
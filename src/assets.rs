@@ -14,6 +14,31 @@ pub fn get_language(name: &str) -> Option<String> {
         .map(|f| f.contents_utf8().unwrap_or_default().to_string())
 }
 
+/// Diacritic folding table of a language, see `Folding`
+pub fn get_folding(language: &str) -> Option<String> {
+    ASSETS
+        .get_file(format!("languages/folding/{language}.json"))
+        .map(|f| f.contents_utf8().unwrap_or_default().to_string())
+}
+
+pub fn get_layout(name: &str) -> Option<String> {
+    ASSETS
+        .get_file(format!("layouts/{name}.json"))
+        .map(|f| f.contents_utf8().unwrap_or_default().to_string())
+}
+
+pub fn get_quotes(language: &str) -> Option<String> {
+    ASSETS
+        .get_file(format!("quotes/{language}.json"))
+        .map(|f| f.contents_utf8().unwrap_or_default().to_string())
+}
+
+pub fn get_snippets(language: &str) -> Option<String> {
+    ASSETS
+        .get_file(format!("snippets/{language}.json"))
+        .map(|f| f.contents_utf8().unwrap_or_default().to_string())
+}
+
 pub fn get_ascii(name: &str) -> Option<String> {
     #[cfg(debug_assertions)]
     {
@@ -67,6 +92,23 @@ pub fn list_languages() -> Vec<String> {
         .unwrap_or_default()
 }
 
+pub fn list_layouts() -> Vec<String> {
+    ASSETS
+        .get_dir("layouts")
+        .map(|dir| {
+            dir.files()
+                .filter(|f| f.path().extension().is_some_and(|ext| ext == "json"))
+                .filter_map(|f| {
+                    f.path()
+                        .file_stem()
+                        .and_then(|n| n.to_str())
+                        .map(String::from)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 pub fn list_ascii() -> Vec<String> {
     #[cfg(debug_assertions)]
     {
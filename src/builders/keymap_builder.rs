@@ -18,6 +18,7 @@ pub static LEADERBOARD_KEYMAP: OnceLock<KeyMap> = OnceLock::new();
 
 const CTRL: KeyModifiers = KeyModifiers::CONTROL;
 const SHIFT: KeyModifiers = KeyModifiers::SHIFT;
+const ALT: KeyModifiers = KeyModifiers::ALT;
 
 #[derive(Debug, Clone, Default)]
 pub struct KeyMap {
@@ -104,7 +105,12 @@ fn build_typing_keymap() -> KeyMap {
     KeyMap::new()
         .bind(KeyCode::Esc, Action::MenuOpen(MenuContext::Root))
         .bind(KeyCode::Backspace, Action::Backspace)
+        .bind_with_mod(CTRL, KeyCode::Backspace, Action::DeleteWord)
+        .bind_with_mod(ALT, KeyCode::Backspace, Action::DeleteWord)
+        .bind_with_mod(CTRL, KeyCode::Char('w'), Action::DeleteWord)
+        .bind(KeyCode::Enter, Action::Input('\n'))
         .bind_with_mod(CTRL, KeyCode::Char('p'), Action::CommandPaletteOpen)
+        .bind_with_mod(CTRL, KeyCode::Char('d'), Action::Finish)
 }
 
 #[rustfmt::skip]
@@ -115,11 +121,15 @@ fn build_results_keymap() -> KeyMap {
         .bind( KeyCode::Char('m'), Action::SetResultVariant(ResultsVariant::Minimal))
         .bind( KeyCode::Char('g'), Action::SetResultVariant(ResultsVariant::Graph))
         .bind( KeyCode::Char('n'), Action::SetResultVariant(ResultsVariant::Neofetch))
+        .bind( KeyCode::Char('h'), Action::SetResultVariant(ResultsVariant::Heatmap))
+        .bind( KeyCode::Char('p'), Action::PracticeNgrams)
         .bind( KeyCode::Up, Action::CycleNextArt)
         .bind( KeyCode::Down, Action::CyclePreviousArt)
         .bind_with_mod(CTRL, KeyCode::Char('p'), Action::CommandPaletteOpen)
         .bind_with_mod(SHIFT, KeyCode::Char('N'), Action::Restart)
         .bind_with_mod(SHIFT, KeyCode::Char('R'), Action::Redo)
+        .bind_with_mod(SHIFT, KeyCode::Char('G'), Action::GhostRace)
+        .bind_with_mod(SHIFT, KeyCode::Char('P'), Action::PracticeMissedWords)
 }
 
 fn build_menu_base_keymap() -> KeyMap {
@@ -175,6 +185,10 @@ fn build_leaderboard_keymap() -> KeyMap {
         .bind(KeyCode::Down, Action::LeaderboardNav(LeaderboardMotion::Down))
         .bind(KeyCode::Up, Action::LeaderboardNav(LeaderboardMotion::Up))
         .bind(KeyCode::Char('g'), Action::LeaderboardNav(LeaderboardMotion::Home))
+        .bind(KeyCode::Enter, Action::LeaderboardReplay)
+        .bind(KeyCode::Char('h'), Action::LeaderboardKeyStats)
+        .bind(KeyCode::Char('p'), Action::LeaderboardPracticeNgrams)
+        .bind(KeyCode::Char('i'), Action::LeaderboardToggleInvalid)
         .bind(KeyCode::Char('m'), Action::LeaderboardSort(SortColumn::Mode))
         .bind(KeyCode::Char('l'), Action::LeaderboardSort(SortColumn::Language))
         .bind(KeyCode::Char('w'), Action::LeaderboardSort(SortColumn::Wpm))
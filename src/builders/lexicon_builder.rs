@@ -1,17 +1,29 @@
-use std::{collections::HashMap, sync::OnceLock};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::OnceLock,
+};
 
-use rand::{Rng, seq::SliceRandom};
+use rand::{
+    Rng, SeedableRng,
+    distr::{Distribution, weighted::WeightedIndex},
+    rngs::StdRng,
+    seq::{IndexedRandom, SliceRandom},
+};
 use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
     assets,
-    config::{Config, Setting},
-    constants::{DEFAULT_LANGUAGE, WPS_TARGET},
+    common::strings::normalize_code,
+    config::{Config, Funbox, QuoteLength, Setting},
+    constants::{DEFAULT_CODE_LANGUAGE, DEFAULT_LANGUAGE},
     error::AppError,
+    key_stats::KeyStats,
     log_debug,
 };
 
-const MIN_POSSIBLE_WORD_COUNT: usize = 100;
+/// Words time tests start with and grow by as the cursor nears the end of the text
+const TIME_MODE_BATCH_WORD_COUNT: usize = 100;
 const SYMBOLS: &[char] = &[
     '@', '#', '$', '%', '&', '*', '(', ')', '+', '-', '/', '=', '?', '<', '>', '^', '_', '`', '{',
     '|', '}', '~',
@@ -28,6 +40,9 @@ const DEFAULT_LEXICON: &[&str] = &[
 const SYMBOL_PROBABILITY: f64 = 0.20;
 const PUNCTUATION_PROBABILITY: f64 = 0.30;
 const NUMBER_PROBABILITY: f64 = 0.15;
+/// Keys attempted fewer times than this are not weighed by the adaptive selection
+const ADAPTIVE_MIN_ATTEMPTS: u32 = 10;
+const VOWELS: &[char] = &['a', 'e', 'i', 'o', 'u', 'A', 'E', 'I', 'O', 'U'];
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Language {
@@ -35,9 +50,48 @@ struct Language {
     words: Vec<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quote {
+    pub text: String,
+    pub source: String,
+    /// Length of the quote text in characters.
+    pub length: usize,
+}
+
+impl Quote {
+    pub fn length_bucket(&self) -> QuoteLength {
+        QuoteLength::from_len(self.length)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct QuoteCollection {
+    language: String,
+    quotes: Vec<Quote>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snippet {
+    pub name: String,
+    pub text: String,
+    /// The `code_*` language the snippet is written in.
+    #[serde(default)]
+    pub language: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SnippetCollection {
+    language: String,
+    snippets: Vec<Snippet>,
+}
+
 #[derive(Debug)]
 pub struct Lexicon {
     pub words: String,
+    /// The quote the words were taken from, if the test is a quote test.
+    pub quote: Option<Quote>,
+    /// The snippet the words were taken from, if the test is a code test.
+    pub snippet: Option<Snippet>,
     builder: LexiconBuilder,
 }
 
@@ -46,30 +100,120 @@ impl Lexicon {
     pub fn new(config: &Config) -> Result<Self, AppError> {
         let mut builder = LexiconBuilder::new();
         let words = builder.generate_test(config)?;
-        Ok(Self { words, builder })
+        let words = builder.apply_funbox(config, words);
+        let quote = builder.last_quote().cloned();
+        let snippet = builder.last_snippet().cloned();
+        Ok(Self {
+            words,
+            quote,
+            snippet,
+            builder,
+        })
     }
 
     /// Regenerates the lexicon composition.
     pub fn regenerate(&mut self, config: &Config) -> Result<(), AppError> {
-        self.words = self.builder.generate_test(config)?;
+        let words = self.builder.generate_test(config)?;
+        self.words = self.builder.apply_funbox(config, words);
+        self.quote = self.builder.last_quote().cloned();
+        self.snippet = self.builder.last_snippet().cloned();
         Ok(())
     }
+
+    /// Uses words that were not generated by the lexicon, i.e the text of a previous run. Where
+    /// they came from is unknown, so the quote and snippet are cleared
+    pub fn replace_words(&mut self, words: String) {
+        self.words = words;
+        self.quote = None;
+        self.snippet = None;
+    }
+
+    /// Uses words rich in the given character sequences, as many as a regular test would have
+    pub fn practice_ngrams(&mut self, config: &Config, ngrams: &[String]) -> Result<(), AppError> {
+        let word_count = LexiconBuilder::word_count(config);
+        self.words =
+            self.builder
+                .generate_ngram_practice(&config.current_language(), ngrams, word_count)?;
+        self.quote = None;
+        self.snippet = None;
+        Ok(())
+    }
+
+    /// Appends another batch of words to a time test so it never runs out of them. Returns the
+    /// words that were added, `None` when the text of the test is fixed (i.e custom words). With
+    /// the nospace funbox the batch carries on the last word instead of following a space
+    pub fn extend(&mut self, config: &Config) -> Result<Option<String>, AppError> {
+        let is_fixed = !config.current_mode().is_time_mode()
+            || config.cli.words.is_some()
+            || self.quote.is_some()
+            || self.snippet.is_some();
+        if is_fixed {
+            return Ok(None);
+        }
+
+        let batch = self.builder.generate_batch(config)?;
+        let batch = self.builder.apply_funbox(config, batch);
+        if !self.words.is_empty() && !config.current_funbox().contains(&Funbox::NoSpace) {
+            self.words.push(' ');
+        }
+        self.words.push_str(&batch);
+        Ok(Some(batch))
+    }
+
+    /// Uses a drill of the given words, each repeated `repetitions` times
+    pub fn practice_words(&mut self, words: &[String], repetitions: usize) {
+        self.words = self.builder.generate_practice(words, repetitions);
+        self.quote = None;
+        self.snippet = None;
+    }
+
+    /// Uses the given statistics to bias the words of adaptive tests toward weak keys
+    pub fn set_key_stats(&mut self, stats: &KeyStats) {
+        self.builder
+            .set_key_weights(stats.weaknesses(ADAPTIVE_MIN_ATTEMPTS));
+    }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct LexiconBuilder {
     languages: HashMap<String, Vec<String>>,
     shuffled_pools: HashMap<String, Vec<usize>>,
-    rng: rand::rngs::ThreadRng,
+    quotes: HashMap<String, Vec<Quote>>,
+    last_quote: Option<Quote>,
+    snippets: HashMap<String, Vec<Snippet>>,
+    last_snippet: Option<Snippet>,
+    /// How poorly each key is typed, used to weigh words when adaptive tests are enabled
+    key_weights: BTreeMap<char, f64>,
+    rng: StdRng,
+}
+
+impl Default for LexiconBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl LexiconBuilder {
     /// Creates a new LexiconBuilder instance.
     pub fn new() -> Self {
+        Self::with_rng(StdRng::from_os_rng())
+    }
+
+    /// Creates a LexiconBuilder that always makes the same choices for the same seed
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(rng: StdRng) -> Self {
         let mut builder = Self {
             languages: HashMap::new(),
             shuffled_pools: HashMap::new(),
-            rng: rand::rng(),
+            quotes: HashMap::new(),
+            last_quote: None,
+            snippets: HashMap::new(),
+            last_snippet: None,
+            key_weights: BTreeMap::new(),
+            rng,
         };
         if builder.load_language(DEFAULT_LANGUAGE).is_err() {
             Self::add_default_words(&mut builder);
@@ -81,30 +225,72 @@ impl LexiconBuilder {
     pub fn generate_test(&mut self, config: &Config) -> Result<String, AppError> {
         // NOTE: im sure we can optimize the sh*t out of this, but good enough for now.
         // TODO: when custom words get implemented take it into consideration here
+        self.last_quote = None;
+        self.last_snippet = None;
         if let Some(custom_words) = &config.cli.words {
             return Ok(custom_words.clone());
         }
         let lang = config.current_language();
-        self.ensure_language_loaded(&lang)?;
 
-        let words = &self.languages[&lang];
-        let shuffled_idxs = &mut self.shuffled_pools.get_mut(&lang).unwrap();
+        // zen tests are built from whatever the user types
+        if config.current_mode().is_zen_mode() {
+            return Ok(String::new());
+        }
+
+        if let Some(length) = config.current_mode().quote_length() {
+            let quote = self.pick_quote(&lang, length)?;
+            let text = quote.text.clone();
+            self.last_quote = Some(quote);
+            return Ok(text);
+        }
+
+        if config.current_mode().is_code_mode() {
+            let snippet = self.pick_snippet(&lang)?;
+            let text = snippet.text.clone();
+            self.last_snippet = Some(snippet);
+            return Ok(text);
+        }
+
+        self.generate_words(config, &lang, Self::word_count(config))
+    }
+
+    /// Generates the next batch of words of an endless time test
+    pub fn generate_batch(&mut self, config: &Config) -> Result<String, AppError> {
+        let lang = config.current_language();
+        self.generate_words(config, &lang, TIME_MODE_BATCH_WORD_COUNT)
+    }
+
+    /// Generates `word_count` random words of the given language, with the extras (punctuation,
+    /// symbols and numbers) the config asks for
+    fn generate_words(
+        &mut self,
+        config: &Config,
+        lang: &str,
+        word_count: usize,
+    ) -> Result<String, AppError> {
+        log_debug!("The word count: {word_count}");
+
+        self.ensure_language_loaded(lang)?;
+
+        let words = &self.languages[lang];
+        let shuffled_idxs = &mut self.shuffled_pools.get_mut(lang).unwrap();
 
         // get new rnadom selection
         shuffled_idxs.shuffle(&mut self.rng);
 
-        // if we are on time mode, we must ensure we genearate enough words even for mythicalrocket
-        let word_count = if config.current_mode().is_time_mode() {
-            (config.current_mode().value() * WPS_TARGET).max(MIN_POSSIBLE_WORD_COUNT)
+        let adaptive_weights = if config.is_enabled(Setting::Adaptive) {
+            Self::adaptive_weights(&self.key_weights, words)
         } else {
-            config.current_mode().value()
+            None
+        };
+        let mut selected_words: Vec<&str> = match adaptive_weights {
+            Some(weights) => (0..word_count)
+                .map(|_| words[weights.sample(&mut self.rng)].as_str())
+                .collect(),
+            None => (0..word_count)
+                .map(|i| words[shuffled_idxs[i % shuffled_idxs.len()]].as_str())
+                .collect(),
         };
-
-        log_debug!("The word count: {word_count}");
-
-        let mut selected_words: Vec<&str> = (0..word_count)
-            .map(|i| words[shuffled_idxs[i % shuffled_idxs.len()]].as_str())
-            .collect();
 
         // re-shuffle
         selected_words.shuffle(&mut self.rng);
@@ -145,6 +331,222 @@ impl LexiconBuilder {
         Ok(result)
     }
 
+    /// Generates a drill of the given words in random order, each one typed `repetitions` times in
+    /// a row
+    pub fn generate_practice(&mut self, words: &[String], repetitions: usize) -> String {
+        let mut words: Vec<&str> = words.iter().map(String::as_str).collect();
+        words.shuffle(&mut self.rng);
+        words
+            .iter()
+            .flat_map(|word| std::iter::repeat_n(*word, repetitions.max(1)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Generates `word_count` words containing the given character sequences, words that contain
+    /// more of them being picked more often. Sequences that no word contains are typed on their own
+    pub fn generate_ngram_practice(
+        &mut self,
+        lang: &str,
+        ngrams: &[String],
+        word_count: usize,
+    ) -> Result<String, AppError> {
+        if ngrams.is_empty() {
+            return Err(AppError::Other("No n-grams to practice".to_string()));
+        }
+        self.ensure_language_loaded(lang)?;
+
+        let hits = |word: &str| {
+            let word = word.to_lowercase();
+            ngrams
+                .iter()
+                .filter(|ngram| word.contains(ngram.as_str()))
+                .count()
+        };
+        let mut candidates: Vec<(&str, usize)> = self.languages[lang]
+            .iter()
+            .map(|word| (word.as_str(), hits(word)))
+            .filter(|(_, hits)| *hits > 0)
+            .collect();
+        for ngram in ngrams {
+            if !candidates
+                .iter()
+                .any(|(word, _)| word.to_lowercase().contains(ngram.as_str()))
+            {
+                candidates.push((ngram.as_str(), 1));
+            }
+        }
+
+        let mut selected_words = (0..word_count)
+            .map(|_| {
+                candidates
+                    .choose_weighted(&mut self.rng, |(_, hits)| *hits)
+                    .map(|(word, _)| *word)
+            })
+            .collect::<Result<Vec<&str>, _>>()
+            .map_err(|e| AppError::Other(e.to_string()))?;
+
+        Self::prevent_consecutive_duplicates(&mut selected_words);
+        Ok(selected_words.join(" "))
+    }
+
+    /// Runs the text of a test through the enabled funboxes, in pipeline order. Code keeps its
+    /// layout, so the funboxes that change it are skipped in code tests
+    pub fn apply_funbox(&mut self, config: &Config, text: String) -> String {
+        let is_code = config.current_mode().is_code_mode();
+        config
+            .current_funbox()
+            .iter()
+            .filter(|funbox| !(is_code && funbox.changes_layout()))
+            .fold(text, |text, &funbox| self.transform(funbox, &text))
+    }
+
+    fn transform(&mut self, funbox: Funbox, text: &str) -> String {
+        match funbox {
+            Funbox::Reverse => map_words(text, |word| word.graphemes(true).rev().collect()),
+            Funbox::Mirror => text
+                .split('\n')
+                .map(|line| line.graphemes(true).rev().collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Funbox::NoVowels => map_words(text, |word| {
+                let consonants: String = word.chars().filter(|c| !VOWELS.contains(c)).collect();
+                // words made only of vowels (i.e `a`) are kept so no word goes missing
+                if consonants.is_empty() {
+                    word.to_string()
+                } else {
+                    consonants
+                }
+            }),
+            Funbox::DoubleLetters => map_words(text, |word| {
+                let mut doubled = String::with_capacity(word.len() * 2);
+                for g in word.graphemes(true) {
+                    doubled.push_str(g);
+                    if g.chars().next().is_some_and(char::is_alphabetic) {
+                        doubled.push_str(g);
+                    }
+                }
+                doubled
+            }),
+            Funbox::Capitalize => map_words(text, |word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            }),
+            Funbox::RandomCase => text
+                .chars()
+                .map(|c| {
+                    if self.rng.random_bool(0.5) {
+                        c.to_uppercase().to_string()
+                    } else {
+                        c.to_lowercase().to_string()
+                    }
+                })
+                .collect(),
+            Funbox::NoSpace => text.replace(' ', ""),
+        }
+    }
+
+    /// Returns the quote picked by the last `generate_test` call, if any.
+    pub fn last_quote(&self) -> Option<&Quote> {
+        self.last_quote.as_ref()
+    }
+
+    /// Picks a random quote of the given length bucket for the given language.
+    ///
+    /// Languages without their own quote collection (i.e `english_5k`) fall back to their base
+    /// language (`english`) and then to the default language. If the collection has no quote in
+    /// the requested bucket we use the closest bucket available.
+    pub fn pick_quote(&mut self, lang: &str, length: QuoteLength) -> Result<Quote, AppError> {
+        let quotes_lang = self.ensure_quotes_loaded(lang)?;
+        let quotes = &self.quotes[&quotes_lang];
+
+        let candidates: Vec<&Quote> = quotes
+            .iter()
+            .filter(|q| q.length_bucket() == length)
+            .collect();
+
+        let quote = if candidates.is_empty() {
+            quotes
+                .iter()
+                .min_by_key(|q| q.length_bucket().value().abs_diff(length.value()))
+        } else {
+            Some(candidates[self.rng.random_range(0..candidates.len())])
+        };
+
+        quote
+            .cloned()
+            .ok_or_else(|| AppError::Other(format!("No quotes available for: {quotes_lang}")))
+    }
+
+    /// Ensures the quotes for the given language (or its fallback) are loaded. Returns the name
+    /// of the collection that ended up being used.
+    fn ensure_quotes_loaded(&mut self, lang: &str) -> Result<String, AppError> {
+        let base = lang.split('_').next().unwrap_or(lang);
+        for candidate in [lang, base, DEFAULT_LANGUAGE] {
+            if self.quotes.contains_key(candidate) {
+                return Ok(candidate.to_string());
+            }
+            if let Some(content) = assets::get_quotes(candidate) {
+                let collection: QuoteCollection = serde_json::from_str(&content)?;
+                if collection.quotes.is_empty() {
+                    continue;
+                }
+                self.quotes
+                    .insert(collection.language.clone(), collection.quotes);
+                return Ok(collection.language);
+            }
+        }
+        Err(AppError::Other(format!("No quotes available for: {lang}")))
+    }
+
+    /// Returns the snippet picked by the last `generate_test` call, if any.
+    pub fn last_snippet(&self) -> Option<&Snippet> {
+        self.last_snippet.as_ref()
+    }
+
+    /// Picks a random code snippet for the given language. Languages without snippets (i.e
+    /// `english` or `code_lua`) fall back to the default code language.
+    pub fn pick_snippet(&mut self, lang: &str) -> Result<Snippet, AppError> {
+        let snippets_lang = self.ensure_snippets_loaded(lang)?;
+        let snippets = &self.snippets[&snippets_lang];
+        let idx = self.rng.random_range(0..snippets.len());
+        Ok(snippets[idx].clone())
+    }
+
+    /// Ensures the snippets for the given language (or the default code language) are loaded.
+    /// Returns the name of the collection that ended up being used.
+    fn ensure_snippets_loaded(&mut self, lang: &str) -> Result<String, AppError> {
+        for candidate in [lang, DEFAULT_CODE_LANGUAGE] {
+            if self.snippets.contains_key(candidate) {
+                return Ok(candidate.to_string());
+            }
+            if let Some(content) = assets::get_snippets(candidate) {
+                let collection: SnippetCollection = serde_json::from_str(&content)?;
+                let snippets: Vec<Snippet> = collection
+                    .snippets
+                    .into_iter()
+                    .map(|snippet| Snippet {
+                        text: normalize_code(&snippet.text),
+                        language: collection.language.clone(),
+                        ..snippet
+                    })
+                    .filter(|snippet| !snippet.text.is_empty())
+                    .collect();
+                if snippets.is_empty() {
+                    continue;
+                }
+                self.snippets.insert(collection.language.clone(), snippets);
+                return Ok(collection.language);
+            }
+        }
+        Err(AppError::Other(format!(
+            "No snippets available for: {lang}"
+        )))
+    }
+
     /// Ensure we at least build from the default words dictionary
     fn add_default_words(builder: &mut Self) {
         let words = DEFAULT_LEXICON
@@ -187,6 +589,45 @@ impl LexiconBuilder {
         Ok(())
     }
 
+    /// Sets how poorly each key is typed. Words with poorly typed keys are picked more often in
+    /// adaptive tests
+    pub fn set_key_weights(&mut self, weights: BTreeMap<char, f64>) {
+        self.key_weights = weights;
+    }
+
+    /// Weighs every word by how poorly its keys are typed. Every word keeps a base weight so the
+    /// test doesn't end up being the same few words over and over. `None` when there's nothing to
+    /// weigh the words by
+    fn adaptive_weights(
+        key_weights: &BTreeMap<char, f64>,
+        words: &[String],
+    ) -> Option<WeightedIndex<f64>> {
+        const BASE_WEIGHT: f64 = 1.0;
+        const WEAKNESS_FACTOR: f64 = 4.0;
+
+        if key_weights.is_empty() {
+            return None;
+        }
+        let weights = words.iter().map(|word| {
+            let mut keys: Vec<char> = word.chars().collect();
+            keys.sort_unstable();
+            keys.dedup();
+            let weakness: f64 = keys.iter().filter_map(|c| key_weights.get(c)).sum();
+            BASE_WEIGHT + weakness * WEAKNESS_FACTOR
+        });
+        WeightedIndex::new(weights).ok()
+    }
+
+    /// How many words a test of the current mode starts with
+    fn word_count(config: &Config) -> usize {
+        // time tests have no word count, they are extended with more batches as they go
+        if config.current_mode().is_time_mode() {
+            TIME_MODE_BATCH_WORD_COUNT
+        } else {
+            config.current_mode().value()
+        }
+    }
+
     /// Prevents back to back duplicated words
     fn prevent_consecutive_duplicates(words: &mut [&str]) {
         for i in 1..words.len() {
@@ -217,12 +658,33 @@ impl LexiconBuilder {
     }
 }
 
+/// Applies `f` to every word of the text, the whitespace between them is kept as is
+fn map_words(text: &str, mut f: impl FnMut(&str) -> String) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut word_start = None;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() {
+            if let Some(start) = word_start.take() {
+                result.push_str(&f(&text[start..i]));
+            }
+            result.push(c);
+        } else if word_start.is_none() {
+            word_start = Some(i);
+        }
+    }
+    if let Some(start) = word_start {
+        result.push_str(&f(&text[start..]));
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        config::{Config, Mode},
+        config::{Config, Mode, QuoteLength},
         constants::MAX_CUSTOM_TIME,
+        key_stats::KeyStat,
     };
     use std::collections::HashSet;
 
@@ -294,25 +756,101 @@ mod tests {
         let mut builder = create_builder();
         let mut config = Config::default();
         let seconds_arr: [usize; 5] = [1, 10, 60, 120, MAX_CUSTOM_TIME];
-        // do we have enough words for all the possible time mode ranges, even for fast typers?
+        // time tests start with one batch no matter how long they are, they grow as they go
         for seconds in seconds_arr {
             config.change_mode(Mode::with_time(seconds)).unwrap();
             let test = builder.generate_test(&config).unwrap();
-            assert!(test.split_whitespace().count() >= WPS_TARGET * seconds);
+            assert_eq!(test.split_whitespace().count(), TIME_MODE_BATCH_WORD_COUNT);
         }
     }
 
     #[test]
-    fn test_ensure_enough_words_low_time_modes() {
+    fn test_extend_time_mode() {
+        let mut config = Config::default();
+        config
+            .change_mode(Mode::with_time(MAX_CUSTOM_TIME))
+            .unwrap();
+        let mut lexicon = Lexicon::new(&config).unwrap();
+        let initial = lexicon.words.clone();
+
+        let batch = lexicon.extend(&config).unwrap().unwrap();
+        assert_eq!(batch.split_whitespace().count(), TIME_MODE_BATCH_WORD_COUNT);
+        assert_eq!(lexicon.words, format!("{initial} {batch}"));
+
+        // word tests have a fixed length
+        config.change_mode(Mode::with_words(10)).unwrap();
+        lexicon.regenerate(&config).unwrap();
+        assert!(lexicon.extend(&config).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_funbox_transforms() {
         let mut builder = create_builder();
         let mut config = Config::default();
-        let seconds = 2;
-        // Without enforcing `MIN_POSSIBLE_WORD_COUNT`, the test would generate only 2 * WPS_TARGET = 12 words.
-        // Typing over 350 WPM in 2 seconds to finish is unrealistic, and having just 12 words show up looks rather odd.
-        // To avoid this, we ensure at least `MIN_POSSIBLE_WORD_COUNT` words for better aesthetics and the cost is minimal
-        config.change_mode(Mode::with_time(seconds)).unwrap();
-        let test = builder.generate_test(&config).unwrap();
-        assert!(test.split_whitespace().count() >= MIN_POSSIBLE_WORD_COUNT);
+        let text = "hello a world\n  tea.";
+        let mut apply = |config: &Config| builder.apply_funbox(config, text.to_string());
+        assert_eq!(apply(&config), text);
+
+        let expected = [
+            (Funbox::Reverse, "olleh a dlrow\n  .aet"),
+            (Funbox::Mirror, "dlrow a olleh\n.aet  "),
+            (Funbox::NoVowels, "hll a wrld\n  t."),
+            (Funbox::DoubleLetters, "hheelllloo aa wwoorrlldd\n  tteeaa."),
+            (Funbox::Capitalize, "Hello A World\n  Tea."),
+            (Funbox::NoSpace, "helloaworld\ntea."),
+        ];
+        for (funbox, transformed) in expected {
+            config.toggle(&Setting::Funbox(funbox)).unwrap();
+            assert_eq!(apply(&config), transformed, "{funbox:?}");
+            config.toggle(&Setting::Funbox(funbox)).unwrap();
+        }
+
+        config.toggle(&Setting::Funbox(Funbox::RandomCase)).unwrap();
+        assert_eq!(apply(&config).to_lowercase(), text);
+
+        // funboxes run in pipeline order no matter the order they were enabled in
+        config.toggle(&Setting::Funbox(Funbox::RandomCase)).unwrap();
+        config.toggle(&Setting::Funbox(Funbox::Capitalize)).unwrap();
+        config.toggle(&Setting::Funbox(Funbox::Reverse)).unwrap();
+        assert_eq!(
+            config.current_funbox(),
+            [Funbox::Reverse, Funbox::Capitalize]
+        );
+        assert_eq!(apply(&config), "Olleh A Dlrow\n  .aet");
+    }
+
+    #[test]
+    fn test_code_keeps_its_layout_under_funbox() {
+        let mut builder = create_builder();
+        let mut config = Config::default();
+        config.change_mode(Mode::Code).unwrap();
+        let code = "fn main() {\n    let x = 1;\n}";
+
+        for funbox in Funbox::ALL.iter().filter(|funbox| funbox.changes_layout()) {
+            config.toggle(&Setting::Funbox(*funbox)).unwrap();
+        }
+        assert_eq!(builder.apply_funbox(&config, code.to_string()), code);
+
+        // the rest still apply, indentation included
+        config.toggle(&Setting::Funbox(Funbox::Reverse)).unwrap();
+        assert_eq!(
+            builder.apply_funbox(&config, code.to_string()),
+            "nf )(niam {\n    tel x = ;1\n}"
+        );
+    }
+
+    #[test]
+    fn test_nospace_time_tests_are_extended() {
+        let mut config = Config::default();
+        config.toggle(&Setting::Funbox(Funbox::NoSpace)).unwrap();
+        let mut lexicon = Lexicon::new(&config).unwrap();
+        assert!(!lexicon.words.contains(' '));
+
+        let len = lexicon.words.len();
+        let batch = lexicon.extend(&config).unwrap().unwrap();
+        assert!(!batch.contains(' '));
+        assert_eq!(lexicon.words.len(), len + batch.len());
+        assert!(!lexicon.words.contains(' '));
     }
 
     #[test]
@@ -344,4 +882,176 @@ mod tests {
 
         assert_ne!(first_test, second_test);
     }
+
+    #[test]
+    fn test_quote_mode_picks_quote_of_requested_length() {
+        let mut builder = create_builder();
+        let mut config = Config::default();
+        for &length in QuoteLength::ALL {
+            config.change_mode(Mode::Quote(length)).unwrap();
+            let test = builder.generate_test(&config).unwrap();
+            let quote = builder
+                .last_quote()
+                .expect("quote mode should pick a quote");
+            assert_eq!(quote.text, test);
+            assert_eq!(quote.length, test.chars().count());
+            assert_eq!(quote.length_bucket(), length);
+            assert!(!quote.source.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_quote_mode_falls_back_to_base_language() {
+        let mut builder = create_builder();
+        let quote = builder
+            .pick_quote("english_5k", QuoteLength::Short)
+            .unwrap();
+        assert!(builder.quotes.contains_key("english"));
+        assert_eq!(quote.length_bucket(), QuoteLength::Short);
+    }
+
+    #[test]
+    fn test_zen_mode_has_no_words() {
+        let mut builder = create_builder();
+        let mut config = Config::default();
+        config.change_mode(Mode::Zen).unwrap();
+        assert!(builder.generate_test(&config).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_non_quote_mode_clears_last_quote() {
+        let mut builder = create_builder();
+        let mut config = Config::default();
+        config.change_mode(Mode::Quote(QuoteLength::Short)).unwrap();
+        builder.generate_test(&config).unwrap();
+        assert!(builder.last_quote().is_some());
+
+        config.change_mode(Mode::with_words(10)).unwrap();
+        builder.generate_test(&config).unwrap();
+        assert!(builder.last_quote().is_none());
+    }
+
+    #[test]
+    fn test_code_mode_picks_snippet_of_current_language() {
+        let mut builder = create_builder();
+        let mut config = Config::default();
+        config.change_language("code_python".to_string());
+        config.change_mode(Mode::Code).unwrap();
+
+        let test = builder.generate_test(&config).unwrap();
+        let snippet = builder
+            .last_snippet()
+            .expect("code mode should pick a snippet");
+        assert_eq!(snippet.language, "code_python");
+        assert_eq!(snippet.text, test);
+        assert!(test.contains('\n'));
+        assert!(!test.contains('\t'));
+    }
+
+    #[test]
+    fn test_code_mode_falls_back_to_default_code_language() {
+        let mut builder = create_builder();
+        let snippet = builder.pick_snippet(DEFAULT_LANGUAGE).unwrap();
+        assert_eq!(snippet.language, DEFAULT_CODE_LANGUAGE);
+    }
+
+    #[test]
+    fn test_ngram_practice_words_contain_ngrams() {
+        let mut builder = create_builder();
+        let ngrams = vec!["th".to_string(), "qzx".to_string()];
+        let test = builder
+            .generate_ngram_practice(DEFAULT_LANGUAGE, &ngrams, 30)
+            .unwrap();
+
+        let words: Vec<&str> = test.split(' ').collect();
+        assert_eq!(words.len(), 30);
+        // no word has "qzx" in it, so it gets typed on its own
+        assert!(
+            words
+                .iter()
+                .all(|w| w.to_lowercase().contains("th") || *w == "qzx")
+        );
+        assert!(
+            builder
+                .generate_ngram_practice(DEFAULT_LANGUAGE, &[], 30)
+                .is_err()
+        );
+    }
+
+    fn adaptive_config() -> Config {
+        let mut config = Config::default();
+        config.change_mode(Mode::with_words(200)).unwrap();
+        config.toggle(&Setting::Adaptive).unwrap();
+        config
+    }
+
+    /// `k` is missed half the time, every other key is typed cleanly at the same speed
+    fn weak_k_stats() -> KeyStats {
+        let mut stats = KeyStats::default();
+        for c in 'a'..='z' {
+            let stat = KeyStat {
+                attempts: 20,
+                misses: if c == 'k' { 10 } else { 0 },
+                latency_ms: 2000,
+                latency_samples: 20,
+                ..Default::default()
+            };
+            stats.add(c, &stat);
+        }
+        stats
+    }
+
+    #[test]
+    fn test_adaptive_selection_is_deterministic_under_seed() {
+        let config = adaptive_config();
+        let generate = || {
+            let mut builder = LexiconBuilder::with_seed(42);
+            builder.set_key_weights(weak_k_stats().weaknesses(ADAPTIVE_MIN_ATTEMPTS));
+            builder.generate_test(&config).unwrap()
+        };
+        assert_eq!(generate(), generate());
+    }
+
+    #[test]
+    fn test_adaptive_selection_favors_weak_keys() {
+        let count_weak = |config: &Config| {
+            let mut builder = LexiconBuilder::with_seed(42);
+            builder.set_key_weights(weak_k_stats().weaknesses(ADAPTIVE_MIN_ATTEMPTS));
+            let test = builder.generate_test(config).unwrap();
+            test.split(' ').filter(|word| word.contains('k')).count()
+        };
+
+        let mut config = adaptive_config();
+        let adaptive = count_weak(&config);
+        config.toggle(&Setting::Adaptive).unwrap();
+        let uniform = count_weak(&config);
+        assert!(adaptive > uniform * 2, "{adaptive} vs {uniform}");
+    }
+
+    #[test]
+    fn test_adaptive_selection_without_stats_is_uniform() {
+        let config = adaptive_config();
+        let mut adaptive = LexiconBuilder::with_seed(7);
+        let mut uniform = LexiconBuilder::with_seed(7);
+        let mut config_off = config.clone();
+        config_off.toggle(&Setting::Adaptive).unwrap();
+        assert_eq!(
+            adaptive.generate_test(&config).unwrap(),
+            uniform.generate_test(&config_off).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_practice_repeats_each_word() {
+        let mut builder = LexiconBuilder::with_seed(1);
+        let words = vec!["alpha".to_string(), "beta".to_string()];
+        let test = builder.generate_practice(&words, 3);
+
+        let drilled: Vec<&str> = test.split(' ').collect();
+        assert_eq!(drilled.len(), 6);
+        // repetitions are typed in a row
+        assert_eq!(drilled[0], drilled[2]);
+        assert_eq!(drilled.iter().filter(|w| **w == "alpha").count(), 3);
+        assert_eq!(drilled.iter().filter(|w| **w == "beta").count(), 3);
+    }
 }
@@ -1,6 +1,7 @@
 use crate::actions::Action;
 use crate::ascii;
-use crate::config::{Config, Setting};
+use crate::config::{Config, Difficulty, ErrorPolicy, Funbox, PaceCaret, QuoteLength, Setting};
+use crate::layout;
 use crate::menu::{MenuContent, MenuContext, MenuItem, MenuVisualizer};
 use crate::modal::ModalContext;
 use crate::theme;
@@ -123,9 +124,17 @@ pub fn build_menu_from_context(ctx: MenuContext, config: &Config) -> MenuContent
         MenuContext::Themes => build_themes_menu(config),
         MenuContext::Time => build_time_menu(config),
         MenuContext::Words => build_words_menu(config),
+        MenuContext::Quote => build_quote_menu(config),
         MenuContext::Language => build_language_menu(config),
+        MenuContext::Layout => build_layout_menu(config),
+        MenuContext::Funbox => build_funbox_menu(config),
         MenuContext::Cursor => build_cursor_menu(config),
         MenuContext::VisibleLines => build_visible_lines_menu(config),
+        MenuContext::ErrorPolicy => build_error_policy_menu(config),
+        MenuContext::Difficulty => build_difficulty_menu(config),
+        MenuContext::PaceCaret => build_pace_caret_menu(config),
+        MenuContext::PracticeRepetitions => build_practice_repetitions_menu(config),
+        MenuContext::Memory => build_memory_menu(config),
         MenuContext::Ascii => build_ascii_menu(config),
         MenuContext::Leaderboard => build_leaderboard_menu(config),
         MenuContext::About => build_about_menu(config),
@@ -138,7 +147,14 @@ fn build_root_menu(_config: &Config) -> MenuContent {
     MenuBuilder::new("Main Menu", MenuContext::Root)
         .submenu("Time", MenuContext::Time).shortcut('t').description("Set test duration")
         .submenu("Words", MenuContext::Words).shortcut('w').description("Set word count")
+        .submenu("Quote", MenuContext::Quote).shortcut('u').description("Type a quote")
+        .action("Zen", Action::SetZen).shortcut('z').description("Free typing, finish with <C-d>").tag("zen").close_on_select()
+        .action("Code", Action::SetCode).shortcut('C').description("Type a code snippet").tag("code").close_on_select()
+        .action("Ghost race", Action::GhostRace).shortcut('g').description("Race against your best run").tag("ghost").close_on_select()
+        .action("Practice mistakes", Action::PracticeMistakes).shortcut('p').description("Drill the words you miss the most").tag("practice").close_on_select()
         .submenu("Language", MenuContext::Language).shortcut('L').description("Select language")
+        .submenu("Layout", MenuContext::Layout).shortcut('k').description("Emulate a keyboard layout")
+        .submenu("Funbox", MenuContext::Funbox).shortcut('f').description("Transform the text of the test")
         .submenu("Options", MenuContext::Options).shortcut('o').description("Configure typing preferences")
         .submenu("Theme", MenuContext::Themes).shortcut('T').description("Available Themes")
         .submenu("Ascii Art", MenuContext::Ascii).shortcut('a').description("View ASCII Arts")
@@ -160,6 +176,17 @@ fn build_options_menu(_config: &Config) -> MenuContent {
         .action("Show notifications", Action::Toggle(Setting::ShowNotifications)).shortcut('N').description("Notifications").tag("option")
         .action("Show hostname (Neofetch results)", Action::Toggle(Setting::ShowHostname)).shortcut('h').description("Hostname (neofetch)").tag("option")
         .action("Save results", Action::Toggle(Setting::SaveResults)).shortcut('r').description("Save Results").tag("option")
+        .action("Auto-close brackets", Action::Toggle(Setting::AutoCloseBrackets)).shortcut('b').description("Auto-close brackets").tag("option")
+        .action("Normalize accents", Action::Toggle(Setting::Normalize)).shortcut('u').description("Precomposed and decomposed accents match (NFC)").tag("option")
+        .action("Lazy mode", Action::Toggle(Setting::Lazy)).shortcut('l').description("Accents are optional, `a` types `á`").tag("option")
+        .action("Adaptive", Action::Toggle(Setting::Adaptive)).shortcut('a').description("Favor words with your weakest keys").tag("option")
+        .action("Blind mode", Action::Toggle(Setting::Blind)).shortcut('B').description("Hide mistakes until the results screen").tag("option")
+        .submenu("Memory mode", MenuContext::Memory).shortcut('m').description("Mask each line after a few seconds on screen")
+        .action("Tape mode", Action::Toggle(Setting::Tape)).shortcut('t').description("Single line scrolling under the caret, instead of lines").tag("option")
+        .submenu("Error policy", MenuContext::ErrorPolicy).shortcut('e').description("How wrong keystrokes are handled")
+        .submenu("Difficulty", MenuContext::Difficulty).shortcut('d').description("When the test fails")
+        .submenu("Pace caret", MenuContext::PaceCaret).shortcut('c').description("Race a caret moving at a target speed")
+        .submenu("Practice repetitions", MenuContext::PracticeRepetitions).shortcut('P').description("Times each word of a drill is typed")
         .build()
 }
 
@@ -180,6 +207,18 @@ fn build_cmd_palette_options_menu(_config: &Config) -> MenuContent {
         .action("Hide Hostname", Action::Disable(Setting::ShowHostname)).description("Hide Hostname").tag("option")
         .action("Enable Result Saving", Action::Enable(Setting::SaveResults)).description("Enable Result Saving").tag("option")
         .action("Disable Result Saving", Action::Disable(Setting::SaveResults)).description("Disable Result Saving").tag("option")
+        .action("Enable Auto-close Brackets", Action::Enable(Setting::AutoCloseBrackets)).description("Enable Auto-close Brackets").tag("option")
+        .action("Disable Auto-close Brackets", Action::Disable(Setting::AutoCloseBrackets)).description("Disable Auto-close Brackets").tag("option")
+        .action("Enable Accent Normalization", Action::Enable(Setting::Normalize)).description("Enable Accent Normalization").tag("option")
+        .action("Disable Accent Normalization", Action::Disable(Setting::Normalize)).description("Disable Accent Normalization").tag("option")
+        .action("Enable Lazy Mode", Action::Enable(Setting::Lazy)).description("Enable Lazy Mode").tag("option")
+        .action("Disable Lazy Mode", Action::Disable(Setting::Lazy)).description("Disable Lazy Mode").tag("option")
+        .action("Enable Adaptive Words", Action::Enable(Setting::Adaptive)).description("Enable Adaptive Words").tag("option")
+        .action("Disable Adaptive Words", Action::Disable(Setting::Adaptive)).description("Disable Adaptive Words").tag("option")
+        .action("Enable Blind Mode", Action::Enable(Setting::Blind)).description("Enable Blind Mode").tag("option")
+        .action("Disable Blind Mode", Action::Disable(Setting::Blind)).description("Disable Blind Mode").tag("option")
+        .action("Enable Tape Mode", Action::Enable(Setting::Tape)).description("Enable Tape Mode").tag("option")
+        .action("Disable Tape Mode", Action::Disable(Setting::Tape)).description("Disable Tape Mode").tag("option")
         .build()
 }
 
@@ -226,6 +265,63 @@ fn build_words_menu(_config: &Config) -> MenuContent {
         .build()
 }
 
+fn build_quote_menu(config: &Config) -> MenuContent {
+    let lengths = QuoteLength::ALL;
+    let mut builder = MenuBuilder::new("Select Quote Length", MenuContext::Quote);
+    for &length in lengths {
+        builder = builder
+            .action(length.label(), Action::SetQuote(length))
+            .description(format!("{} quote", length.label()))
+            .tag("quote")
+            .close_on_select();
+    }
+    let mut menu = builder.build();
+
+    if let Some(current) = config.current_mode().quote_length()
+        && let Some(idx) = lengths.iter().position(|&l| l == current)
+    {
+        menu.set_current_index(idx);
+    }
+
+    menu
+}
+
+fn build_zen_menu(_config: &Config) -> MenuContent {
+    MenuBuilder::new("Zen", MenuContext::Root)
+        .action("Zen", Action::SetZen)
+        .description("Free typing, finish with <C-d>")
+        .tag("zen")
+        .close_on_select()
+        .build()
+}
+
+fn build_ghost_race_menu(_config: &Config) -> MenuContent {
+    MenuBuilder::new("Ghost race", MenuContext::Root)
+        .action("Ghost race", Action::GhostRace)
+        .description("Race against your best run")
+        .tag("ghost")
+        .close_on_select()
+        .build()
+}
+
+fn build_practice_mistakes_menu(_config: &Config) -> MenuContent {
+    MenuBuilder::new("Practice mistakes", MenuContext::Root)
+        .action("Practice mistakes", Action::PracticeMistakes)
+        .description("Drill the words you miss the most")
+        .tag("practice")
+        .close_on_select()
+        .build()
+}
+
+fn build_code_menu(_config: &Config) -> MenuContent {
+    MenuBuilder::new("Code", MenuContext::Root)
+        .action("Code", Action::SetCode)
+        .description("Type a code snippet")
+        .tag("code")
+        .close_on_select()
+        .build()
+}
+
 fn build_language_menu(config: &Config) -> MenuContent {
     use crate::builders::lexicon_builder::LexiconBuilder;
     let languages = LexiconBuilder::available_languages();
@@ -249,6 +345,42 @@ fn build_language_menu(config: &Config) -> MenuContent {
     menu
 }
 
+fn build_layout_menu(config: &Config) -> MenuContent {
+    let layouts = layout::list_layouts();
+    let mut builder = MenuBuilder::new("Select Layout", MenuContext::Layout);
+    for name in &layouts {
+        builder = builder
+            .action(name.clone(), Action::SetLayout(name.clone()))
+            .description(name.clone())
+            .tag("layout")
+            .close_on_select();
+    }
+    let mut menu = builder.build();
+
+    if let Some(idx) = layouts
+        .iter()
+        .position(|name| *name == config.current_layout())
+    {
+        menu.set_current_index(idx);
+    }
+
+    menu
+}
+
+fn build_funbox_menu(_config: &Config) -> MenuContent {
+    let mut builder = MenuBuilder::new("Funbox", MenuContext::Funbox);
+    for &funbox in Funbox::ALL {
+        builder = builder
+            .action(
+                funbox.display_name(),
+                Action::Toggle(Setting::Funbox(funbox)),
+            )
+            .description(funbox.description())
+            .tag("funbox");
+    }
+    builder.build()
+}
+
 fn build_cursor_menu(config: &Config) -> MenuContent {
     use crate::variants::CursorVariant;
     let variants = CursorVariant::all();
@@ -274,6 +406,122 @@ fn build_cursor_menu(config: &Config) -> MenuContent {
     menu
 }
 
+fn build_error_policy_menu(config: &Config) -> MenuContent {
+    let policies = ErrorPolicy::ALL;
+    let mut builder = MenuBuilder::new("Select Error Policy", MenuContext::ErrorPolicy);
+    for &policy in policies {
+        builder = builder
+            .action(policy.display_name(), Action::SetErrorPolicy(policy))
+            .description(format!("{} error policy", policy.display_name()))
+            .tag("error policy")
+            .close_on_select();
+    }
+    let mut menu = builder.build();
+
+    let current = config.current_error_policy();
+    if let Some(idx) = policies.iter().position(|&p| p == current) {
+        menu.set_current_index(idx);
+    }
+
+    menu
+}
+
+fn build_difficulty_menu(config: &Config) -> MenuContent {
+    let difficulties = Difficulty::ALL;
+    let mut builder = MenuBuilder::new("Select Difficulty", MenuContext::Difficulty);
+    for &difficulty in difficulties {
+        builder = builder
+            .action(difficulty.label(), Action::SetDifficulty(difficulty))
+            .description(difficulty.description())
+            .tag("difficulty")
+            .close_on_select();
+    }
+
+    let min_accuracy = match config.current_min_accuracy() {
+        0 => "off".to_string(),
+        accuracy => format!("{accuracy}%"),
+    };
+    let min_wpm = match config.current_min_wpm() {
+        0 => "off".to_string(),
+        wpm => wpm.to_string(),
+    };
+    builder = builder
+        .action(
+            format!("Min accuracy ({min_accuracy})"),
+            Action::ModalOpen(ModalContext::MinAccuracy),
+        )
+        .description("Fail when the live accuracy drops below this")
+        .tag("difficulty")
+        .action(
+            format!("Min WPM ({min_wpm})"),
+            Action::ModalOpen(ModalContext::MinWpm),
+        )
+        .description("Fail when the live WPM drops below this")
+        .tag("difficulty");
+
+    let mut menu = builder.build();
+
+    let current = config.current_difficulty();
+    if let Some(idx) = difficulties.iter().position(|&d| d == current) {
+        menu.set_current_index(idx);
+    }
+
+    menu
+}
+
+#[rustfmt::skip]
+fn build_pace_caret_menu(config: &Config) -> MenuContent {
+    let builder = MenuBuilder::new("Select Pace Caret", MenuContext::PaceCaret)
+        .action("Off", Action::SetPaceCaret(PaceCaret::Off)).shortcut('o').description("No pace caret").tag("pace caret").close_on_select()
+        .action("Average", Action::SetPaceCaret(PaceCaret::Average)).shortcut('a').description("Average WPM of this mode").tag("pace caret").close_on_select()
+        .action("Best", Action::SetPaceCaret(PaceCaret::Best)).shortcut('b').description("Personal best WPM of this mode").tag("pace caret").close_on_select()
+        .action("Custom", Action::ModalOpen(ModalContext::PaceCaretWpm)).shortcut('c').description("Custom pace caret WPM").tag("pace caret");
+
+    let mut menu = builder.build();
+    let idx = match config.current_pace_caret() {
+        PaceCaret::Off => 0,
+        PaceCaret::Average => 1,
+        PaceCaret::Best => 2,
+        PaceCaret::Fixed(_) => 3,
+    };
+    menu.set_current_index(idx);
+    menu
+}
+
+#[rustfmt::skip]
+fn build_practice_repetitions_menu(config: &Config) -> MenuContent {
+    let builder = MenuBuilder::new("Select Practice Repetitions", MenuContext::PracticeRepetitions)
+        .action("Repeat 1x", Action::SetPracticeRepetitions(1)).shortcut('1').description("Type each word once").tag("practice").close_on_select()
+        .action("Repeat 2x", Action::SetPracticeRepetitions(2)).shortcut('2').description("Type each word 2 times").tag("practice").close_on_select()
+        .action("Repeat 3x", Action::SetPracticeRepetitions(3)).shortcut('3').description("Type each word 3 times").tag("practice").close_on_select()
+        .action("Repeat 5x", Action::SetPracticeRepetitions(5)).shortcut('5').description("Type each word 5 times").tag("practice").close_on_select();
+
+    let mut menu = builder.build();
+    let current = config.current_practice_repetitions();
+    if let Some(idx) = [1, 2, 3, 5].iter().position(|count| *count == current) {
+        menu.set_current_index(idx);
+    }
+    menu
+}
+
+#[rustfmt::skip]
+fn build_memory_menu(config: &Config) -> MenuContent {
+    let builder = MenuBuilder::new("Select Memory Mode", MenuContext::Memory)
+        .action("Off", Action::SetMemory(0)).shortcut('o').description("Lines are never masked").tag("memory").close_on_select()
+        .action("1 second", Action::SetMemory(1)).shortcut('1').description("Mask each line after 1 second").tag("memory").close_on_select()
+        .action("2 seconds", Action::SetMemory(2)).shortcut('2').description("Mask each line after 2 seconds").tag("memory").close_on_select()
+        .action("3 seconds", Action::SetMemory(3)).shortcut('3').description("Mask each line after 3 seconds").tag("memory").close_on_select()
+        .action("5 seconds", Action::SetMemory(5)).shortcut('5').description("Mask each line after 5 seconds").tag("memory").close_on_select()
+        .action("10 seconds", Action::SetMemory(10)).description("Mask each line after 10 seconds").tag("memory").close_on_select();
+
+    let mut menu = builder.build();
+    let current = config.current_memory();
+    if let Some(idx) = [0, 1, 2, 3, 5, 10].iter().position(|secs| *secs == current) {
+        menu.set_current_index(idx);
+    }
+    menu
+}
+
 #[rustfmt::skip]
 fn build_visible_lines_menu(config: &Config) -> MenuContent {
     let builder = MenuBuilder::new("Select Line Count", MenuContext::VisibleLines)
@@ -340,8 +588,20 @@ fn build_cmd_palette(config: &Config) -> MenuContent {
     let menu_builders_fns = [
         build_time_menu,
         build_words_menu,
+        build_quote_menu,
+        build_zen_menu,
+        build_code_menu,
+        build_ghost_race_menu,
+        build_practice_mistakes_menu,
         build_language_menu,
+        build_layout_menu,
+        build_funbox_menu,
         build_cmd_palette_options_menu,
+        build_error_policy_menu,
+        build_difficulty_menu,
+        build_pace_caret_menu,
+        build_practice_repetitions_menu,
+        build_memory_menu,
         build_themes_menu,
         build_ascii_menu,
         build_visible_lines_menu,
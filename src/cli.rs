@@ -1,8 +1,10 @@
+use crate::config::{Difficulty, ErrorPolicy, Funbox, PaceCaret, QuoteLength};
 use crate::constants::{
     DEFAULT_LINE_COUNT, MAX_CUSTOM_TIME, MAX_CUSTOM_WORD_COUNT, MIN_CUSTOM_TIME,
     MIN_CUSTOM_WORD_COUNT,
 };
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 /// The CLI arguments
 #[derive(Parser, Debug, Default, Clone)]
@@ -14,6 +16,9 @@ use clap::Parser;
         .multiple(false)
 ))]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Test duration in seconds. Enforces Time mode.
     #[arg(short = 't', long = "time", group = "mode", value_name = "SECONDS")]
     pub time: Option<u64>,
@@ -22,10 +27,27 @@ pub struct Cli {
     #[arg(short = 'w', long = "words", group = "mode", value_name = "WORDS")]
     pub words: Option<String>,
 
+    /// Type through the text file at the given path, keeping its line breaks. Use `-` to read
+    /// from stdin. Enforces Word mode.
+    #[arg(short = 'f', long = "file", group = "mode", value_name = "PATH")]
+    pub file: Option<PathBuf>,
+
     /// Number (count) of words to type
     #[arg(short = 'c', long = "count", group = "mode", value_name = "COUNT")]
     pub words_count: Option<usize>,
 
+    /// Type a quote of the given length: short, medium, long. Enforces Quote mode.
+    #[arg(short = 'q', long = "quote", group = "mode", value_name = "LENGTH")]
+    pub quote: Option<String>,
+
+    /// Free typing with no target text. End the test with <C-d>. Enforces Zen mode.
+    #[arg(short = 'z', long = "zen", group = "mode")]
+    pub zen: bool,
+
+    /// Type a multi-line snippet of the current `code_*` language. Enforces Code mode.
+    #[arg(long = "code", group = "mode")]
+    pub code: bool,
+
     /// Include numbers in the test word pool
     #[arg(short = 'n', long = "numbers")]
     pub use_numbers: bool,
@@ -46,6 +68,10 @@ pub struct Cli {
     #[arg(long = "theme")]
     pub theme: Option<String>,
 
+    /// Keyboard layout emulated on a QWERTY keyboard: qwerty, dvorak, colemak, workman or a user layout
+    #[arg(long = "layout", value_name = "LAYOUT")]
+    pub layout: Option<String>,
+
     /// The ASCII art used in the `Neofetch` results
     #[arg(long = "ascii")]
     pub ascii: Option<String>,
@@ -58,7 +84,7 @@ pub struct Cli {
     #[arg(long = "picker", value_name = "STYLE")]
     pub picker: Option<String>,
 
-    /// Results style variant: minimal, neofetch, graph
+    /// Results style variant: minimal, neofetch, graph, heatmap
     #[arg(long = "results", value_name = "STYLE")]
     pub results: Option<String>,
 
@@ -70,6 +96,14 @@ pub struct Cli {
     )]
     pub visible_lines: u8,
 
+    /// Tape mode, the text is a single line that scrolls horizontally under the caret
+    #[arg(long = "tape")]
+    pub tape: bool,
+
+    /// Column the caret stays at in tape mode
+    #[arg(long = "tape-column", value_name = "COLUMN")]
+    pub tape_column: Option<u16>,
+
     /// Enables debug mode
     #[cfg(debug_assertions)]
     #[arg(short = 'd', long = "debug")]
@@ -92,6 +126,50 @@ pub struct Cli {
     #[arg(long = "hide-hostname")]
     pub hide_hostname: bool,
 
+    /// How wrong keystrokes are handled: normal, stop-on-letter, stop-on-word
+    #[arg(long = "error-policy", value_name = "POLICY")]
+    pub error_policy: Option<String>,
+
+    /// Fail the test on mistakes: normal, expert (word with errors), master (any mistake)
+    #[arg(long = "difficulty", value_name = "LEVEL")]
+    pub difficulty: Option<String>,
+
+    /// Fail the test when the live accuracy drops below this percentage (0 disables it)
+    #[arg(long = "min-accuracy", value_name = "PERCENT")]
+    pub min_accuracy: Option<u8>,
+
+    /// Fail the test when the live WPM drops below this value (0 disables it)
+    #[arg(long = "min-wpm", value_name = "WPM")]
+    pub min_wpm: Option<u16>,
+
+    /// Show a pace caret moving at a target speed: off, average, best or a WPM value
+    #[arg(long = "pace-caret", value_name = "PACE")]
+    pub pace_caret: Option<String>,
+
+    /// Automatically type closing brackets once their opening bracket is typed correctly
+    #[arg(long = "auto-close")]
+    pub auto_close: bool,
+
+    /// Match precomposed and decomposed characters (i.e `é` and `e` + `´`) by comparing them in NFC
+    #[arg(long = "normalize")]
+    pub normalize: bool,
+
+    /// Lazy mode, diacritics are optional (i.e `a` for `á`). Lazy results are tracked separately
+    #[arg(long = "lazy")]
+    pub lazy: bool,
+
+    /// Blind mode, typed text isn't colored by correctness until the results screen
+    #[arg(long = "blind")]
+    pub blind: bool,
+
+    /// Memory mode, each line is shown for this many seconds before it's masked (0 disables it)
+    #[arg(long = "memory", value_name = "SECS")]
+    pub memory: Option<u8>,
+
+    /// Text transforms, comma separated: reverse, mirror, no-vowels, double-letters, capitalize, random-case, nospace
+    #[arg(long = "funbox", value_name = "FUNBOX", value_delimiter = ',')]
+    pub funbox: Vec<String>,
+
     /// Do not save tests results
     #[arg(long = "no-save")]
     pub no_save: bool,
@@ -101,6 +179,15 @@ pub struct Cli {
     pub reset: bool,
 }
 
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Replay a saved test result keystroke by keystroke. Result ids are listed in the leaderboard
+    Replay {
+        /// Id of the result to replay
+        id: i64,
+    },
+}
+
 impl Cli {
     pub fn validate(&self) -> Result<(), String> {
         if let Some(t) = self.time
@@ -125,6 +212,32 @@ impl Cli {
                 "Word count must be between {MIN_CUSTOM_WORD_COUNT} and {MAX_CUSTOM_WORD_COUNT}"
             ));
         }
+
+        if let Some(q) = &self.quote {
+            q.parse::<QuoteLength>()?;
+        }
+
+        if let Some(p) = &self.error_policy {
+            p.parse::<ErrorPolicy>()?;
+        }
+
+        if let Some(d) = &self.difficulty {
+            d.parse::<Difficulty>()?;
+        }
+
+        for f in &self.funbox {
+            f.parse::<Funbox>()?;
+        }
+
+        if let Some(a) = self.min_accuracy
+            && a > 100
+        {
+            return Err("Minimum accuracy must be between 0 and 100".to_string());
+        }
+
+        if let Some(p) = &self.pace_caret {
+            p.parse::<PaceCaret>()?;
+        }
         Ok(())
     }
 
@@ -169,12 +282,12 @@ mod tests {
         assert!(cli.validate().is_err());
         assert_eq!(
             cli.validate().unwrap_err(),
-            "Time must be between 1 and 300 seconds"
+            "Time must be between 1 and 3600 seconds"
         );
 
         // too high
         let cli = Cli {
-            time: Some(301),
+            time: Some(3601),
             words_count: None,
             ..Default::default()
         };
@@ -250,6 +363,90 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_quote_length() {
+        let cli = Cli {
+            quote: Some("long".to_string()),
+            ..Default::default()
+        };
+        assert!(cli.validate().is_ok());
+
+        let cli = Cli {
+            quote: Some("huge".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            cli.validate().unwrap_err(),
+            "Invalid quote length 'huge', must be one of: short, medium, long"
+        );
+    }
+
+    #[test]
+    fn test_validate_failure_options() {
+        let cli = Cli {
+            difficulty: Some("expert".to_string()),
+            min_accuracy: Some(95),
+            ..Default::default()
+        };
+        assert!(cli.validate().is_ok());
+
+        let cli = Cli {
+            difficulty: Some("hard".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            cli.validate().unwrap_err(),
+            "Invalid difficulty 'hard', must be one of: normal, expert, master"
+        );
+
+        let cli = Cli {
+            min_accuracy: Some(101),
+            ..Default::default()
+        };
+        assert_eq!(
+            cli.validate().unwrap_err(),
+            "Minimum accuracy must be between 0 and 100"
+        );
+    }
+
+    #[test]
+    fn test_validate_error_policy() {
+        let cli = Cli {
+            error_policy: Some("stop-on-word".to_string()),
+            ..Default::default()
+        };
+        assert!(cli.validate().is_ok());
+
+        let cli = Cli {
+            error_policy: Some("strict".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            cli.validate().unwrap_err(),
+            "Invalid error policy 'strict', must be one of: normal, stop-on-letter, stop-on-word"
+        );
+    }
+
+    #[test]
+    fn test_parse_funbox() {
+        let cli = Cli::try_parse_from(["termitype", "--funbox", "reverse,nospace"]).unwrap();
+        assert_eq!(cli.funbox, ["reverse", "nospace"]);
+        assert!(cli.validate().is_ok());
+
+        let cli = Cli::try_parse_from(["termitype", "--funbox", "reverse,upside-down"]).unwrap();
+        assert!(cli.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_replay_command() {
+        let cli = Cli::try_parse_from(["termitype", "replay", "42"]).unwrap();
+        assert_eq!(cli.command, Some(Command::Replay { id: 42 }));
+
+        let cli = Cli::try_parse_from(["termitype"]).unwrap();
+        assert_eq!(cli.command, None);
+        assert!(Cli::try_parse_from(["termitype", "replay", "last"]).is_err());
+    }
+
     #[test]
     fn test_reset_flag() {
         let cli = Cli {
@@ -1,8 +1,13 @@
 use crate::{
+    common::strings::normalize_document,
     constants::APP_NAME,
     error::{AppError, AppResult},
 };
-use std::{env, fs, path::PathBuf};
+use std::{
+    env, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
 
 /// Grabs the intenal config dir where the logger and persitant state files live
 pub fn config_dir() -> AppResult<PathBuf> {
@@ -60,3 +65,24 @@ pub fn create_file(path: &PathBuf) -> AppResult<fs::File> {
         .open(path)
         .map_err(AppError::from)
 }
+
+/// Reads the document at the given path to be used as the test text. A path of `-` reads from
+/// stdin. The content is normalized, see `normalize_document`.
+pub fn read_document(path: &Path) -> AppResult<String> {
+    let content = if path == Path::new("-") {
+        let mut buf = String::new();
+        io::stdin().read_to_string(&mut buf)?;
+        buf
+    } else {
+        fs::read_to_string(path)?
+    };
+
+    let document = normalize_document(&content);
+    if document.is_empty() {
+        return Err(AppError::other(format!(
+            "Nothing to type in: {}",
+            path.display()
+        )));
+    }
+    Ok(document)
+}
@@ -0,0 +1,174 @@
+use crate::assets;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// A user perceived character: a base character along with its combining marks, an emoji
+/// sequence, a wide CJK character... Most of them are a single `char`, but not all of them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grapheme(String);
+
+impl Grapheme {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The first code point of the cluster, i.e `e` for `e` + `U+0301`
+    pub fn base(&self) -> char {
+        self.0.chars().next().unwrap_or_default()
+    }
+
+    /// Number of terminal cells the cluster takes when displayed
+    pub fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    /// Whether the cluster separates two words, see `tracker::is_separator`
+    pub fn is_separator(&self) -> bool {
+        self.0 == " " || self.0 == "\n"
+    }
+
+    /// Whether `input` types this cluster. With `normalize` precomposed and decomposed forms
+    /// are the same thing (`é` and `e` + `U+0301`), as if both were normalized to NFC
+    pub fn is_typed_by(&self, input: &str, normalize: bool) -> bool {
+        if normalize {
+            self.0.nfd().eq(input.nfd())
+        } else {
+            self.0 == input
+        }
+    }
+
+    /// Whether `input` is the start of this cluster and more keystrokes are needed to finish it
+    pub fn expects_more(&self, input: &str, normalize: bool) -> bool {
+        if normalize {
+            let target: Vec<char> = self.0.nfd().collect();
+            let input: Vec<char> = input.nfd().collect();
+            input.len() < target.len() && target.starts_with(&input)
+        } else {
+            input.len() < self.0.len() && self.0.starts_with(input)
+        }
+    }
+}
+
+impl From<char> for Grapheme {
+    fn from(c: char) -> Self {
+        Self(c.to_string())
+    }
+}
+
+impl From<&str> for Grapheme {
+    fn from(s: &str) -> Self {
+        Self(s.to_string())
+    }
+}
+
+impl PartialEq<char> for Grapheme {
+    fn eq(&self, other: &char) -> bool {
+        let mut chars = self.0.chars();
+        chars.next() == Some(*other) && chars.next().is_none()
+    }
+}
+
+impl fmt::Display for Grapheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Diacritics a language lets lazy mode leave out, i.e `á` typed as `a`. Each language has its
+/// own table in `assets/languages/folding/<language>.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Folding {
+    folding: BTreeMap<char, char>,
+}
+
+impl Folding {
+    /// The folding table of the language. Languages without one don't fold anything
+    pub fn for_language(language: &str) -> Self {
+        assets::get_folding(language)
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.folding.is_empty()
+    }
+
+    /// The grapheme without its diacritics, if it has any the table knows about. Decomposed
+    /// graphemes are composed first so `e` + `U+0301` folds just like `é` does
+    pub fn fold(&self, grapheme: &Grapheme) -> Option<Grapheme> {
+        let composed: String = grapheme.as_str().nfc().collect();
+        let folded: String = composed
+            .chars()
+            .map(|c| self.folding.get(&c).copied().unwrap_or(c))
+            .collect();
+        (folded != composed).then_some(Grapheme(folded))
+    }
+}
+
+/// Splits the text into its extended grapheme clusters
+pub fn graphemes(text: &str) -> impl Iterator<Item = Grapheme> + '_ {
+    text.graphemes(true).map(Grapheme::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graphemes() {
+        let clusters: Vec<Grapheme> = graphemes("ae\u{301} 日👍🏽").collect();
+        assert_eq!(clusters.len(), 5);
+        assert_eq!(clusters[0], 'a');
+        assert_eq!(clusters[1].as_str(), "e\u{301}");
+        assert_eq!(clusters[1].base(), 'e');
+        assert_eq!(clusters[1].width(), 1);
+        assert!(clusters[2].is_separator());
+        assert_eq!(clusters[3].width(), 2);
+        assert_eq!(clusters[4].as_str(), "👍🏽");
+    }
+
+    #[test]
+    fn test_normalized_matching() {
+        let precomposed = Grapheme::from('é');
+        let decomposed = Grapheme::from("e\u{301}");
+
+        assert!(!precomposed.is_typed_by("e\u{301}", false));
+        assert!(precomposed.is_typed_by("e\u{301}", true));
+        assert!(decomposed.is_typed_by("é", true));
+
+        assert!(!precomposed.expects_more("e", false));
+        assert!(precomposed.expects_more("e", true));
+        assert!(decomposed.expects_more("e", false));
+        assert!(!decomposed.expects_more("e\u{301}", false));
+        assert!(!Grapheme::from('a').expects_more("a", true));
+    }
+
+    #[test]
+    fn test_folding() {
+        let folding = Folding::for_language("spanish");
+        assert_eq!(
+            folding.fold(&Grapheme::from('ñ')),
+            Some(Grapheme::from('n'))
+        );
+        assert_eq!(
+            folding.fold(&Grapheme::from('Á')),
+            Some(Grapheme::from('A'))
+        );
+        assert_eq!(
+            folding.fold(&Grapheme::from("e\u{301}")),
+            Some(Grapheme::from('e'))
+        );
+        assert_eq!(folding.fold(&Grapheme::from('n')), None);
+
+        // every bundled table loads
+        for language in assets::list_languages() {
+            if assets::get_folding(&language).is_some() {
+                assert!(!Folding::for_language(&language).is_empty(), "{language}");
+            }
+        }
+        assert!(Folding::for_language("english").is_empty());
+    }
+}
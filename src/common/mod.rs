@@ -1,2 +1,3 @@
 pub mod filesystem;
+pub mod grapheme;
 pub mod strings;
@@ -68,9 +68,61 @@ pub fn fuzzy_match(text: &str, pattern: &str) -> bool {
     false
 }
 
+/// Normalizes a document so it can be used as a typing test text.
+///
+/// Runs of whitespace within a line are collapsed into a single space, lines are trimmed and
+/// consecutive blank lines collapse into a single paragraph break. Line breaks are kept as `\n`.
+pub fn normalize_document(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut pending_breaks = 0;
+
+    for line in text.lines() {
+        let mut words = line.split_whitespace().peekable();
+        if words.peek().is_none() {
+            if !result.is_empty() {
+                pending_breaks += 1;
+            }
+            continue;
+        }
+
+        if !result.is_empty() {
+            let breaks = if pending_breaks > 0 { 2 } else { 1 };
+            result.extend(std::iter::repeat_n('\n', breaks));
+        }
+        pending_breaks = 0;
+
+        for (i, word) in words.enumerate() {
+            if i > 0 {
+                result.push(' ');
+            }
+            result.push_str(word);
+        }
+    }
+
+    result
+}
+
+/// Normalizes a code snippet so it can be used as a typing test text.
+///
+/// Indentation is preserved with tabs expanded to four spaces. Trailing whitespace is trimmed from
+/// every line and blank lines at the start and end of the snippet are dropped.
+pub fn normalize_code(text: &str) -> String {
+    let lines: Vec<String> = text
+        .lines()
+        .map(|line| line.replace('\t', "    ").trim_end().to_string())
+        .collect();
+
+    let start = lines.iter().position(|line| !line.is_empty());
+    let end = lines.iter().rposition(|line| !line.is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end].join("\n"),
+        _ => String::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::fuzzy_match;
+    use super::{fuzzy_match, normalize_code, normalize_document};
 
     #[test]
     fn empty_pattern_always_matches() {
@@ -119,4 +171,27 @@ mod tests {
         assert!(fuzzy_match("Solarized Light", "sl"));
         assert!(!fuzzy_match("nord", "nz"));
     }
+
+    #[test]
+    fn normalize_document_keeps_lines_and_paragraphs() {
+        let text = "  first   line\r\nsecond\tline \n\n\n\nnew paragraph\n\n";
+        assert_eq!(
+            normalize_document(text),
+            "first line\nsecond line\n\nnew paragraph"
+        );
+    }
+
+    #[test]
+    fn normalize_code_keeps_indentation() {
+        let text = "\nfn main() {  \r\n\tprintln!(\"hi\");\n\n    return;\n}\n\n";
+        assert_eq!(
+            normalize_code(text),
+            "fn main() {\n    println!(\"hi\");\n\n    return;\n}"
+        );
+    }
+
+    #[test]
+    fn normalize_document_blank_text_is_empty() {
+        assert_eq!(normalize_document(" \n\t\n "), "");
+    }
 }
@@ -2,12 +2,15 @@ use crate::{
     ascii,
     cli::Cli,
     constants::{
-        DEFAULT_ASCII_ART, DEFAULT_LANGUAGE, DEFAULT_LINE_COUNT, DEFAULT_THEME,
-        DEFAULT_TIME_MODE_DURATION_IN_SECS, DEFAULT_WORD_MODE_COUNT,
+        DEFAULT_ASCII_ART, DEFAULT_LANGUAGE, DEFAULT_LAYOUT, DEFAULT_LINE_COUNT,
+        DEFAULT_PRACTICE_REPETITIONS, DEFAULT_TAPE_COLUMN, DEFAULT_THEME,
+        DEFAULT_TIME_MODE_DURATION_IN_SECS, DEFAULT_WORD_MODE_COUNT, MAX_PACE_CARET_WPM,
     },
     error::AppError,
+    layout::KeyboardLayout,
     persistence::Persistence,
     theme::Theme,
+    tracker::TrackerOptions,
     variants::{CursorVariant, PickerVariant, ResultsVariant},
 };
 use anyhow::Result;
@@ -24,6 +27,19 @@ pub enum Setting {
     ShowNotifications,
     ShowHostname,
     SaveResults,
+    AutoCloseBrackets,
+    /// Precomposed and decomposed characters match each other
+    Normalize,
+    /// Diacritics are optional, `a` types `á`
+    Lazy,
+    /// Bias word selection toward the keys the user types poorly
+    Adaptive,
+    /// Transform the text of the test, see `Funbox`
+    Funbox(Funbox),
+    /// Typed text isn't colored by correctness until the results screen
+    Blind,
+    /// The text is a single line that scrolls under the caret
+    Tape,
 }
 
 impl Setting {
@@ -31,7 +47,14 @@ impl Setting {
     pub fn should_trigger_restart(&self) -> bool {
         matches!(
             self,
-            Setting::Symbols | Setting::Numbers | Setting::Punctuation
+            Setting::Symbols
+                | Setting::Numbers
+                | Setting::Punctuation
+                | Setting::AutoCloseBrackets
+                | Setting::Normalize
+                | Setting::Lazy
+                | Setting::Adaptive
+                | Setting::Funbox(_)
         )
     }
 }
@@ -40,13 +63,36 @@ impl Setting {
 pub enum ModeKind {
     Time,
     Words,
+    Quote,
+    Zen,
+    Code,
+    Practice,
 }
 
-/// Represents a typing test mode, either time-based or word-count based.
+/// Length bucket of the quote used in quote mode.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteLength {
+    Short,
+    #[default]
+    Medium,
+    Long,
+}
+
+/// Represents a typing test mode: time-based, word-count based, a quote, free typing (zen) or a
+/// code snippet.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Mode {
     Time(usize),
     Words(usize),
+    Quote(QuoteLength),
+    /// Free typing with no target text. The test only ends when the user finishes it.
+    Zen,
+    /// A multi-line code snippet of the current `code_*` language, indentation included.
+    Code,
+    /// A drill of words the user got wrong, each repeated the given number of times. The words
+    /// are picked by the app, so this is never the configured mode.
+    Practice(usize),
 }
 
 impl ModeKind {
@@ -54,6 +100,328 @@ impl ModeKind {
         match self {
             ModeKind::Time => "Time".to_string(),
             ModeKind::Words => "Words".to_string(),
+            ModeKind::Quote => "Quote".to_string(),
+            ModeKind::Zen => "Zen".to_string(),
+            ModeKind::Code => "Code".to_string(),
+            ModeKind::Practice => "Practice".to_string(),
+        }
+    }
+}
+
+impl QuoteLength {
+    pub const ALL: &'static [Self] = &[Self::Short, Self::Medium, Self::Long];
+
+    /// Upper bound (inclusive) in characters of the short bucket.
+    pub const SHORT_MAX_CHARS: usize = 100;
+    /// Upper bound (inclusive) in characters of the medium bucket.
+    pub const MEDIUM_MAX_CHARS: usize = 300;
+
+    /// Returns the bucket a text of `len` characters falls into.
+    ///
+    /// ```
+    /// use termitype::config::QuoteLength;
+    /// assert_eq!(QuoteLength::from_len(42), QuoteLength::Short);
+    /// assert_eq!(QuoteLength::from_len(250), QuoteLength::Medium);
+    /// assert_eq!(QuoteLength::from_len(1000), QuoteLength::Long);
+    /// ```
+    pub fn from_len(len: usize) -> Self {
+        if len <= Self::SHORT_MAX_CHARS {
+            Self::Short
+        } else if len <= Self::MEDIUM_MAX_CHARS {
+            Self::Medium
+        } else {
+            Self::Long
+        }
+    }
+
+    /// Numeric representation used when persisting results.
+    pub fn value(&self) -> usize {
+        match self {
+            Self::Short => 0,
+            Self::Medium => 1,
+            Self::Long => 2,
+        }
+    }
+
+    /// Inverse of [`QuoteLength::value`].
+    pub fn from_value(value: usize) -> Option<Self> {
+        Self::ALL.iter().copied().find(|l| l.value() == value)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Short => "short",
+            Self::Medium => "medium",
+            Self::Long => "long",
+        }
+    }
+}
+
+/// Error for a value that is none of the labels of a setting, i.e `Invalid quote length 'tiny',
+/// must be one of: short, medium, long`
+fn invalid_label(setting: &str, value: &str, labels: &[&str]) -> String {
+    format!(
+        "Invalid {setting} '{value}', must be one of: {}",
+        labels.join(", ")
+    )
+}
+
+impl std::str::FromStr for QuoteLength {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "short" => Ok(Self::Short),
+            "medium" => Ok(Self::Medium),
+            "long" => Ok(Self::Long),
+            _ => Err(invalid_label(
+                "quote length",
+                s,
+                &["short", "medium", "long"],
+            )),
+        }
+    }
+}
+
+/// How the typing test reacts to wrong keystrokes.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorPolicy {
+    /// Wrong keystrokes are recorded and the cursor moves on.
+    #[default]
+    Normal,
+    /// Wrong keystrokes are rejected, the cursor only advances on the correct key.
+    StopOnLetter,
+    /// Words can't be submitted until they are typed correctly.
+    StopOnWord,
+}
+
+impl ErrorPolicy {
+    pub const ALL: &'static [Self] = &[Self::Normal, Self::StopOnLetter, Self::StopOnWord];
+
+    /// Identifier used in the CLI and when persisting results.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::StopOnLetter => "stop-on-letter",
+            Self::StopOnWord => "stop-on-word",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Normal => "Normal",
+            Self::StopOnLetter => "Stop on letter",
+            Self::StopOnWord => "Stop on word",
+        }
+    }
+}
+
+impl std::str::FromStr for ErrorPolicy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|policy| policy.label() == s.to_lowercase())
+            .ok_or_else(|| {
+                let labels: Vec<&str> = Self::ALL.iter().map(Self::label).collect();
+                invalid_label("error policy", s, &labels)
+            })
+    }
+}
+
+/// How forgiving a typing test is. Harder difficulties fail the test instead of completing it.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Difficulty {
+    /// Mistakes are allowed.
+    #[default]
+    Normal,
+    /// The test fails when a word is submitted with errors.
+    Expert,
+    /// The test fails on the first mistake.
+    Master,
+}
+
+impl Difficulty {
+    pub const ALL: &'static [Self] = &[Self::Normal, Self::Expert, Self::Master];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Expert => "expert",
+            Self::Master => "master",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Normal => "Mistakes are allowed",
+            Self::Expert => "Fail when a word is submitted with errors",
+            Self::Master => "Fail on the first mistake",
+        }
+    }
+}
+
+impl std::str::FromStr for Difficulty {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|difficulty| difficulty.label() == s.to_lowercase())
+            .ok_or_else(|| {
+                let labels: Vec<&str> = Self::ALL.iter().map(Self::label).collect();
+                invalid_label("difficulty", s, &labels)
+            })
+    }
+}
+
+/// Transforms applied to the text of a test once it's generated. Any number of them can be
+/// combined, they always run in the order they are declared in.
+///
+/// Hiding words doesn't change what is typed so it's not a funbox, blind and memory mode mask
+/// the text on screen instead.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Funbox {
+    /// Every word is typed backwards.
+    Reverse,
+    /// Every line is typed backwards, last word first.
+    Mirror,
+    /// Vowels are left out of the words that have other letters.
+    NoVowels,
+    /// Every letter is typed twice.
+    DoubleLetters,
+    /// Every word starts with a capital letter.
+    Capitalize,
+    /// Letters are randomly upper or lower case.
+    RandomCase,
+    /// Words are typed without spaces between them.
+    NoSpace,
+}
+
+impl Funbox {
+    pub const ALL: &'static [Self] = &[
+        Self::Reverse,
+        Self::Mirror,
+        Self::NoVowels,
+        Self::DoubleLetters,
+        Self::Capitalize,
+        Self::RandomCase,
+        Self::NoSpace,
+    ];
+
+    /// Identifier used in the CLI and when persisting results.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Reverse => "reverse",
+            Self::Mirror => "mirror",
+            Self::NoVowels => "no-vowels",
+            Self::DoubleLetters => "double-letters",
+            Self::Capitalize => "capitalize",
+            Self::RandomCase => "random-case",
+            Self::NoSpace => "nospace",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Reverse => "Reverse",
+            Self::Mirror => "Mirror",
+            Self::NoVowels => "No vowels",
+            Self::DoubleLetters => "Double letters",
+            Self::Capitalize => "Capitalize",
+            Self::RandomCase => "rAnDoM cAsE",
+            Self::NoSpace => "No space",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Reverse => "Type every word backwards",
+            Self::Mirror => "Type every line backwards",
+            Self::NoVowels => "Leave the vowels out",
+            Self::DoubleLetters => "Type every letter twice",
+            Self::Capitalize => "Capitalize every word",
+            Self::RandomCase => "Randomly upper or lower case letters",
+            Self::NoSpace => "No spaces between words",
+        }
+    }
+
+    /// Whether the transform moves text across words, which would break the indentation of code.
+    pub fn changes_layout(&self) -> bool {
+        matches!(self, Self::Mirror | Self::NoSpace)
+    }
+
+    /// Labels of the given funboxes as saved along the results, i.e `reverse,random-case`.
+    pub fn join_labels(funboxes: &[Self]) -> String {
+        funboxes
+            .iter()
+            .map(Self::label)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl std::str::FromStr for Funbox {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|funbox| funbox.label() == s.to_lowercase())
+            .ok_or_else(|| {
+                let labels: Vec<&str> = Self::ALL.iter().map(Self::label).collect();
+                invalid_label("funbox", s, &labels)
+            })
+    }
+}
+
+/// Speed of the pace caret that moves through the text alongside the user.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PaceCaret {
+    #[default]
+    Off,
+    /// A fixed WPM.
+    Fixed(u16),
+    /// The average WPM of the saved results of the current mode.
+    Average,
+    /// The personal best WPM of the current mode.
+    Best,
+}
+
+impl PaceCaret {
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self, Self::Off)
+    }
+
+    /// Identifier used in the CLI, fixed paces are just their WPM.
+    pub fn label(&self) -> String {
+        match self {
+            Self::Off => "off".to_string(),
+            Self::Fixed(wpm) => wpm.to_string(),
+            Self::Average => "average".to_string(),
+            Self::Best => "best".to_string(),
+        }
+    }
+}
+
+impl std::str::FromStr for PaceCaret {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "average" => Ok(Self::Average),
+            "best" => Ok(Self::Best),
+            wpm => match wpm.parse::<u16>() {
+                Ok(wpm) if (1..=MAX_PACE_CARET_WPM).contains(&wpm) => Ok(Self::Fixed(wpm)),
+                _ => Err(format!(
+                    "Invalid pace caret '{s}', must be one of: off, average, best or a WPM \
+                     between 1 and {MAX_PACE_CARET_WPM}"
+                )),
+            },
         }
     }
 }
@@ -81,20 +449,28 @@ impl Mode {
         }
     }
 
-    /// Returns the value of the mode: seconds for time mode, word count for words mode.
+    /// Returns the value of the mode: seconds for time mode, word count for words mode, the
+    /// length bucket for quote mode (see [`QuoteLength::value`]), repetitions of each word for
+    /// practice mode and `0` for zen and code mode.
     pub fn value(&self) -> usize {
         match self {
             Mode::Time(secs) => *secs,
             Mode::Words(count) => *count,
+            Mode::Practice(repetitions) => *repetitions,
+            Mode::Quote(length) => length.value(),
+            Mode::Zen | Mode::Code => 0,
         }
     }
 
     /// Returns the kind of the test.
     pub fn kind(&self) -> ModeKind {
-        if let Mode::Time(_) = self {
-            ModeKind::Time
-        } else {
-            ModeKind::Words
+        match self {
+            Mode::Time(_) => ModeKind::Time,
+            Mode::Words(_) => ModeKind::Words,
+            Mode::Quote(_) => ModeKind::Quote,
+            Mode::Zen => ModeKind::Zen,
+            Mode::Code => ModeKind::Code,
+            Mode::Practice(_) => ModeKind::Practice,
         }
     }
 
@@ -108,6 +484,35 @@ impl Mode {
         matches!(self, Mode::Words(_))
     }
 
+    /// Returns true if this is a quote mode.
+    pub fn is_quote_mode(&self) -> bool {
+        matches!(self, Mode::Quote(_))
+    }
+
+    /// Returns true if this is a zen (free typing) mode.
+    pub fn is_zen_mode(&self) -> bool {
+        matches!(self, Mode::Zen)
+    }
+
+    /// Returns true if this is a code snippet mode.
+    pub fn is_code_mode(&self) -> bool {
+        matches!(self, Mode::Code)
+    }
+
+    /// Returns true if this is a practice drill.
+    pub fn is_practice_mode(&self) -> bool {
+        matches!(self, Mode::Practice(_))
+    }
+
+    /// Returns the quote length bucket if this is a quote mode.
+    pub fn quote_length(&self) -> Option<QuoteLength> {
+        if let Mode::Quote(length) = self {
+            Some(*length)
+        } else {
+            None
+        }
+    }
+
     /// Creates a new time-based Mode with the specified duration in seconds.
     ///
     /// This is a convenience method for creating a time-limited typing test.
@@ -180,6 +585,10 @@ impl fmt::Display for Mode {
         match self {
             Mode::Time(t) => write!(f, "Time: {t} seconds"),
             Mode::Words(w) => write!(f, "Words: {w}"),
+            Mode::Quote(l) => write!(f, "Quote: {}", l.label()),
+            Mode::Zen => write!(f, "Zen"),
+            Mode::Code => write!(f, "Code"),
+            Mode::Practice(r) => write!(f, "Practice: {r}x"),
         }
     }
 }
@@ -190,12 +599,55 @@ impl Default for Mode {
     }
 }
 
+/// How much of the text the typing area gives away while the test is on
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TextVisibility {
+    /// Typed text is not colored by correctness (blind mode)
+    pub blind: bool,
+    /// Lines are masked once they have been on screen this long during the test (memory mode)
+    pub memory: Option<Duration>,
+}
+
+impl TextVisibility {
+    /// Whether anything is hidden, the results screen then reveals the annotated text
+    pub fn hides_text(&self) -> bool {
+        self.blind || self.memory.is_some()
+    }
+}
+
+/// How the text is laid out in the typing area
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextFlow {
+    /// The text wraps into lines, this many of them are visible
+    Lines(u8),
+    /// A single line that scrolls under a caret fixed at this column (tape mode)
+    Tape(u16),
+}
+
+impl Default for TextFlow {
+    fn default() -> Self {
+        Self::Lines(DEFAULT_LINE_COUNT)
+    }
+}
+
+impl TextFlow {
+    pub fn visible_lines(&self) -> u8 {
+        match self {
+            Self::Lines(count) => *count,
+            Self::Tape(_) => 1,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigState {
     #[serde(default)]
     pub mode: Mode,
     #[serde(default)]
     pub language: Option<String>,
+    /// Keyboard layout emulated on top of QWERTY
+    #[serde(default)]
+    pub layout: Option<String>,
     #[serde(default)]
     pub ascii: Option<String>,
     #[serde(default)]
@@ -217,6 +669,11 @@ pub struct ConfigState {
     #[serde(default)]
     pub lines: u8,
     #[serde(default)]
+    pub tape: bool,
+    /// Column the caret stays at in tape mode
+    #[serde(default = "default_tape_column")]
+    pub tape_column: u16,
+    #[serde(default)]
     pub hide_live_wpm: bool,
     #[serde(default)]
     pub hide_notifications: bool,
@@ -224,6 +681,45 @@ pub struct ConfigState {
     pub hide_hostname: bool,
     #[serde(default)]
     pub no_save: bool,
+    #[serde(default)]
+    pub auto_close_brackets: bool,
+    #[serde(default)]
+    pub normalize: bool,
+    #[serde(default)]
+    pub lazy: bool,
+    #[serde(default)]
+    pub adaptive: bool,
+    /// Active text transforms, kept in pipeline order
+    #[serde(default)]
+    pub funbox: Vec<Funbox>,
+    #[serde(default)]
+    pub blind: bool,
+    /// Seconds each line is shown during the test before it's masked. `0` disables memory mode
+    #[serde(default)]
+    pub memory: u8,
+    #[serde(default)]
+    pub error_policy: ErrorPolicy,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    /// Minimum live accuracy (percentage) before the test fails. `0` disables it
+    #[serde(default)]
+    pub min_accuracy: u8,
+    /// Minimum live WPM before the test fails. `0` disables it
+    #[serde(default)]
+    pub min_wpm: u16,
+    #[serde(default)]
+    pub pace_caret: PaceCaret,
+    /// Times each word of a practice drill is repeated
+    #[serde(default = "default_practice_repetitions")]
+    pub practice_repetitions: u8,
+}
+
+fn default_practice_repetitions() -> u8 {
+    DEFAULT_PRACTICE_REPETITIONS
+}
+
+fn default_tape_column() -> u16 {
+    DEFAULT_TAPE_COLUMN
 }
 
 impl Default for ConfigState {
@@ -235,7 +731,10 @@ impl Default for ConfigState {
             symbols: false,
             punctuation: false,
             lines: DEFAULT_LINE_COUNT,
+            tape: false,
+            tape_column: DEFAULT_TAPE_COLUMN,
             language: Some(DEFAULT_LANGUAGE.to_string()),
+            layout: Some(DEFAULT_LAYOUT.to_string()),
             theme: Some(DEFAULT_THEME.to_string()),
             ascii: Some(ascii::get_default_art_by_os().to_string()),
             cursor_variant: CursorVariant::default(),
@@ -245,6 +744,19 @@ impl Default for ConfigState {
             hide_notifications: false,
             hide_hostname: true,
             no_save: false,
+            auto_close_brackets: false,
+            normalize: false,
+            lazy: false,
+            adaptive: false,
+            funbox: Vec::new(),
+            blind: false,
+            memory: 0,
+            error_policy: ErrorPolicy::default(),
+            difficulty: Difficulty::default(),
+            min_accuracy: 0,
+            min_wpm: 0,
+            pace_caret: PaceCaret::default(),
+            practice_repetitions: DEFAULT_PRACTICE_REPETITIONS,
         }
     }
 }
@@ -302,6 +814,20 @@ impl Config {
             self.state.mode = Mode::with_words(count)
         }
 
+        if let Some(length_str) = &cli.quote
+            && let Ok(length) = length_str.parse::<QuoteLength>()
+        {
+            self.state.mode = Mode::Quote(length);
+        }
+
+        if cli.zen {
+            self.state.mode = Mode::Zen;
+        }
+
+        if cli.code {
+            self.state.mode = Mode::Code;
+        }
+
         if let Some(words_str) = &cli.words {
             let word_count = words_str.split_whitespace().count();
             self.state.mode = Mode::with_words(word_count);
@@ -320,6 +846,18 @@ impl Config {
             }
         }
 
+        if let Some(layout_str) = &cli.layout {
+            if KeyboardLayout::load(layout_str).is_ok() {
+                self.state.layout = Some(layout_str.clone())
+            } else if !self.state.hide_notifications && !cli.hide_notifications {
+                crate::notify_warning!(format!(
+                    "Layout '{}' could not be loaded. Keeping current layout '{}'",
+                    layout_str,
+                    self.current_layout()
+                ));
+            }
+        }
+
         if let Some(ascii_str) = &cli.ascii
             && ascii_str.parse::<ascii::Ascii>().is_ok()
         {
@@ -372,8 +910,70 @@ impl Config {
             self.state.no_save = true;
         }
 
+        if cli.auto_close {
+            self.state.auto_close_brackets = true;
+        }
+
+        if cli.normalize {
+            self.state.normalize = true;
+        }
+
+        if cli.lazy {
+            self.state.lazy = true;
+        }
+
+        if cli.blind {
+            self.state.blind = true;
+        }
+
+        if let Some(memory) = cli.memory {
+            self.state.memory = memory;
+        }
+
+        if !cli.funbox.is_empty() {
+            let mut funbox: Vec<Funbox> =
+                cli.funbox.iter().filter_map(|f| f.parse().ok()).collect();
+            funbox.sort();
+            funbox.dedup();
+            self.state.funbox = funbox;
+        }
+
+        if let Some(policy_str) = &cli.error_policy
+            && let Ok(policy) = policy_str.parse::<ErrorPolicy>()
+        {
+            self.state.error_policy = policy;
+        }
+
+        if let Some(difficulty_str) = &cli.difficulty
+            && let Ok(difficulty) = difficulty_str.parse::<Difficulty>()
+        {
+            self.state.difficulty = difficulty;
+        }
+
+        if let Some(accuracy) = cli.min_accuracy {
+            self.state.min_accuracy = accuracy;
+        }
+
+        if let Some(wpm) = cli.min_wpm {
+            self.state.min_wpm = wpm;
+        }
+
+        if let Some(pace_str) = &cli.pace_caret
+            && let Ok(pace) = pace_str.parse::<PaceCaret>()
+        {
+            self.state.pace_caret = pace;
+        }
+
         self.state.lines = cli.visible_lines;
 
+        if cli.tape {
+            self.state.tape = true;
+        }
+
+        if let Some(column) = cli.tape_column {
+            self.state.tape_column = column;
+        }
+
         #[cfg(debug_assertions)]
         if cli.debug {
             self.state.debug = true;
@@ -420,6 +1020,13 @@ impl Config {
         self.state.lines
     }
 
+    pub fn current_layout(&self) -> String {
+        self.state
+            .layout
+            .clone()
+            .unwrap_or_else(|| DEFAULT_LAYOUT.to_string())
+    }
+
     pub fn current_ascii_art(&self) -> String {
         self.state
             .ascii
@@ -435,6 +1042,10 @@ impl Config {
         self.state.language = Some(lang);
     }
 
+    pub fn change_layout(&mut self, layout: String) {
+        self.state.layout = Some(layout);
+    }
+
     pub fn change_ascii_art(&mut self, ascii_art: String) {
         self.state.ascii = Some(ascii_art);
     }
@@ -461,6 +1072,83 @@ impl Config {
         self.state.lines = count;
     }
 
+    pub fn current_error_policy(&self) -> ErrorPolicy {
+        self.state.error_policy
+    }
+
+    pub fn change_error_policy(&mut self, policy: ErrorPolicy) {
+        self.state.error_policy = policy;
+    }
+
+    /// Returns how the text is laid out in the typing area, wrapped lines or a tape
+    pub fn text_flow(&self) -> TextFlow {
+        if self.is_enabled(Setting::Tape) {
+            TextFlow::Tape(self.state.tape_column)
+        } else {
+            TextFlow::Lines(self.current_line_count())
+        }
+    }
+
+    pub fn current_memory(&self) -> u8 {
+        self.state.memory
+    }
+
+    pub fn change_memory(&mut self, secs: u8) {
+        self.state.memory = secs;
+    }
+
+    /// Returns how much of the text the typing area shows, see blind and memory modes
+    pub fn text_visibility(&self) -> TextVisibility {
+        TextVisibility {
+            blind: self.is_enabled(Setting::Blind),
+            memory: (self.state.memory > 0).then(|| Duration::from_secs(self.state.memory as u64)),
+        }
+    }
+
+    pub fn current_funbox(&self) -> &[Funbox] {
+        &self.state.funbox
+    }
+
+    pub fn current_difficulty(&self) -> Difficulty {
+        self.state.difficulty
+    }
+
+    pub fn change_difficulty(&mut self, difficulty: Difficulty) {
+        self.state.difficulty = difficulty;
+    }
+
+    pub fn current_min_accuracy(&self) -> u8 {
+        self.state.min_accuracy
+    }
+
+    pub fn change_min_accuracy(&mut self, accuracy: u8) {
+        self.state.min_accuracy = accuracy;
+    }
+
+    pub fn current_min_wpm(&self) -> u16 {
+        self.state.min_wpm
+    }
+
+    pub fn change_min_wpm(&mut self, wpm: u16) {
+        self.state.min_wpm = wpm;
+    }
+
+    pub fn current_pace_caret(&self) -> PaceCaret {
+        self.state.pace_caret
+    }
+
+    pub fn change_pace_caret(&mut self, pace: PaceCaret) {
+        self.state.pace_caret = pace;
+    }
+
+    pub fn current_practice_repetitions(&self) -> u8 {
+        self.state.practice_repetitions.max(1)
+    }
+
+    pub fn change_practice_repetitions(&mut self, repetitions: u8) {
+        self.state.practice_repetitions = repetitions.max(1);
+    }
+
     pub fn should_hide_live_wpm(&self) -> bool {
         self.state.hide_live_wpm
     }
@@ -486,6 +1174,26 @@ impl Config {
             Setting::ShowNotifications => !self.state.hide_notifications,
             Setting::ShowHostname => !self.state.hide_hostname,
             Setting::SaveResults => !self.state.no_save,
+            Setting::AutoCloseBrackets => self.state.auto_close_brackets,
+            Setting::Normalize => self.state.normalize,
+            Setting::Lazy => self.state.lazy,
+            Setting::Adaptive => self.state.adaptive,
+            Setting::Funbox(funbox) => self.state.funbox.contains(&funbox),
+            Setting::Blind => self.state.blind,
+            Setting::Tape => self.state.tape,
+        }
+    }
+
+    /// Returns the options the typing test tracker runs with
+    pub fn tracker_options(&self) -> TrackerOptions {
+        TrackerOptions {
+            auto_close_brackets: self.is_enabled(Setting::AutoCloseBrackets),
+            error_policy: self.current_error_policy(),
+            difficulty: self.current_difficulty(),
+            min_accuracy: self.current_min_accuracy(),
+            min_wpm: self.current_min_wpm(),
+            normalize: self.is_enabled(Setting::Normalize),
+            lazy: self.is_enabled(Setting::Lazy),
         }
     }
 
@@ -499,9 +1207,25 @@ impl Config {
             Setting::ShowNotifications => self.state.hide_notifications = !self.state.hide_notifications,
             Setting::ShowHostname => self.state.hide_hostname = !self.state.hide_hostname,
             Setting::SaveResults => self.state.no_save = !self.state.no_save,
+            Setting::AutoCloseBrackets => self.state.auto_close_brackets = !self.state.auto_close_brackets,
+            Setting::Normalize => self.state.normalize = !self.state.normalize,
+            Setting::Lazy => self.state.lazy = !self.state.lazy,
+            Setting::Adaptive => self.state.adaptive = !self.state.adaptive,
+            Setting::Funbox(funbox) => self.toggle_funbox(*funbox),
+            Setting::Blind => self.state.blind = !self.state.blind,
+            Setting::Tape => self.state.tape = !self.state.tape,
         };
         Ok(())
     }
+
+    fn toggle_funbox(&mut self, funbox: Funbox) {
+        if self.state.funbox.contains(&funbox) {
+            self.state.funbox.retain(|f| *f != funbox);
+        } else {
+            self.state.funbox.push(funbox);
+            self.state.funbox.sort();
+        }
+    }
 }
 
 #[cfg(test)]
@@ -573,7 +1297,11 @@ mod tests {
         assert!(!config.state.hide_notifications);
         assert!(!config.should_hide_notifications());
         config.toggle(&Setting::ShowNotifications).unwrap();
-        assert!(config.should_hide_notifications())
+        assert!(config.should_hide_notifications());
+
+        assert!(!config.is_enabled(Setting::Adaptive));
+        config.toggle(&Setting::Adaptive).unwrap();
+        assert!(config.is_enabled(Setting::Adaptive));
     }
 
     #[test]
@@ -594,6 +1322,204 @@ mod tests {
         assert_eq!(config.current_mode().value(), 79);
     }
 
+    #[test]
+    fn test_quote_mode() {
+        let mut config = Config::default();
+        config.change_mode(Mode::Quote(QuoteLength::Long)).unwrap();
+
+        assert!(config.current_mode().is_quote_mode());
+        assert!(!config.current_mode().is_time_mode());
+        assert!(!config.current_mode().is_words_mode());
+        assert_eq!(config.current_mode().kind(), ModeKind::Quote);
+        assert_eq!(config.current_mode().kind().to_display(), "Quote");
+        assert_eq!(
+            config.current_mode().quote_length(),
+            Some(QuoteLength::Long)
+        );
+        assert_eq!(config.current_mode().duration(), None);
+        assert_eq!(config.current_mode().count(), None);
+    }
+
+    #[test]
+    fn test_zen_mode() {
+        let mut config = Config::default();
+        config.change_mode(Mode::Zen).unwrap();
+
+        assert!(config.current_mode().is_zen_mode());
+        assert_eq!(config.current_mode().kind(), ModeKind::Zen);
+        assert_eq!(config.current_mode().kind().to_display(), "Zen");
+        assert_eq!(config.current_mode().value(), 0);
+
+        let cli = Cli {
+            zen: true,
+            ..Default::default()
+        };
+        let mut config = Config {
+            cli: cli.clone(),
+            ..Default::default()
+        };
+        config.apply_cli_args(cli);
+        assert_eq!(config.current_mode(), Mode::Zen);
+    }
+
+    #[test]
+    fn test_code_mode() {
+        let cli = Cli {
+            code: true,
+            auto_close: true,
+            ..Default::default()
+        };
+        let mut config = Config {
+            cli: cli.clone(),
+            ..Default::default()
+        };
+        config.apply_cli_args(cli);
+
+        assert!(config.current_mode().is_code_mode());
+        assert_eq!(config.current_mode().kind().to_display(), "Code");
+        assert_eq!(config.current_mode().value(), 0);
+        assert!(config.tracker_options().auto_close_brackets);
+
+        config.toggle(&Setting::AutoCloseBrackets).unwrap();
+        assert!(!config.tracker_options().auto_close_brackets);
+        assert!(Setting::AutoCloseBrackets.should_trigger_restart());
+    }
+
+    #[test]
+    fn test_text_flow() {
+        let mut config = Config::default();
+        assert_eq!(config.text_flow(), TextFlow::Lines(DEFAULT_LINE_COUNT));
+
+        config.toggle(&Setting::Tape).unwrap();
+        assert_eq!(config.text_flow(), TextFlow::Tape(DEFAULT_TAPE_COLUMN));
+        assert_eq!(config.text_flow().visible_lines(), 1);
+
+        let cli = Cli {
+            tape_column: Some(8),
+            ..Default::default()
+        };
+        config.apply_cli_args(cli);
+        assert_eq!(config.text_flow(), TextFlow::Tape(8));
+    }
+
+    #[test]
+    fn test_text_visibility() {
+        let mut config = Config::default();
+        assert!(!config.text_visibility().hides_text());
+
+        config.toggle(&Setting::Blind).unwrap();
+        assert!(config.text_visibility().blind);
+        assert!(!Setting::Blind.should_trigger_restart());
+
+        config.change_memory(3);
+        assert_eq!(
+            config.text_visibility().memory,
+            Some(Duration::from_secs(3))
+        );
+        config.change_memory(0);
+        assert_eq!(config.text_visibility().memory, None);
+
+        let cli = Cli {
+            memory: Some(5),
+            ..Default::default()
+        };
+        config.apply_cli_args(cli);
+        assert_eq!(config.current_memory(), 5);
+    }
+
+    #[test]
+    fn test_error_policy() {
+        let mut config = Config::default();
+        assert_eq!(config.current_error_policy(), ErrorPolicy::Normal);
+
+        config.change_error_policy(ErrorPolicy::StopOnWord);
+        assert_eq!(
+            config.tracker_options().error_policy,
+            ErrorPolicy::StopOnWord
+        );
+
+        let cli = Cli {
+            error_policy: Some("stop-on-letter".to_string()),
+            ..Default::default()
+        };
+        config.apply_cli_args(cli);
+        assert_eq!(config.current_error_policy(), ErrorPolicy::StopOnLetter);
+
+        for &policy in ErrorPolicy::ALL {
+            assert_eq!(policy.label().parse::<ErrorPolicy>(), Ok(policy));
+        }
+        assert!("nope".parse::<ErrorPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_failure_options() {
+        let mut config = Config::default();
+        assert_eq!(config.current_difficulty(), Difficulty::Normal);
+
+        let cli = Cli {
+            difficulty: Some("master".to_string()),
+            min_accuracy: Some(90),
+            min_wpm: Some(60),
+            ..Default::default()
+        };
+        config.apply_cli_args(cli);
+
+        let options = config.tracker_options();
+        assert_eq!(options.difficulty, Difficulty::Master);
+        assert_eq!(options.min_accuracy, 90);
+        assert_eq!(options.min_wpm, 60);
+    }
+
+    #[test]
+    fn test_pace_caret() {
+        let mut config = Config::default();
+        assert!(!config.current_pace_caret().is_enabled());
+
+        let cli = Cli {
+            pace_caret: Some("85".to_string()),
+            ..Default::default()
+        };
+        config.apply_cli_args(cli);
+        assert_eq!(config.current_pace_caret(), PaceCaret::Fixed(85));
+
+        for pace in [
+            PaceCaret::Off,
+            PaceCaret::Fixed(120),
+            PaceCaret::Average,
+            PaceCaret::Best,
+        ] {
+            assert_eq!(pace.label().parse::<PaceCaret>(), Ok(pace));
+        }
+        assert!("0".parse::<PaceCaret>().is_err());
+        assert!("fast".parse::<PaceCaret>().is_err());
+    }
+
+    #[test]
+    fn test_quote_length_roundtrip() {
+        for &length in QuoteLength::ALL {
+            assert_eq!(QuoteLength::from_value(length.value()), Some(length));
+            assert_eq!(length.label().parse::<QuoteLength>(), Ok(length));
+        }
+        assert_eq!(QuoteLength::from_value(42), None);
+        assert!("tiny".parse::<QuoteLength>().is_err());
+    }
+
+    #[test]
+    fn test_quote_cli_arg_sets_quote_mode() {
+        let cli = Cli {
+            quote: Some("short".to_string()),
+            ..Default::default()
+        };
+        let mut config = Config {
+            cli: cli.clone(),
+            ..Default::default()
+        };
+
+        config.apply_cli_args(cli);
+
+        assert_eq!(config.current_mode(), Mode::Quote(QuoteLength::Short));
+    }
+
     #[test]
     fn test_mode_type() {
         let mut config = Config::default();
@@ -1,25 +1,36 @@
 pub const APP_NAME: &str = env!("CARGO_PKG_NAME");
 pub const DEFAULT_LINE_COUNT: u8 = 3;
 pub const DEFAULT_LANGUAGE: &str = "english";
+pub const DEFAULT_CODE_LANGUAGE: &str = "code_rust";
 pub const DEFAULT_THEME: &str = "tokyonight";
+pub const DEFAULT_LAYOUT: &str = "qwerty";
 pub const DEFAULT_ASCII_ART: &str = "Termitype";
 pub const DEFAULT_RESULTS_VARIANT: &str = "graph";
 
 pub const WPS_TARGET: usize = 6; // words per second target; approx 350 / 60
 
 pub const MIN_CUSTOM_TIME: usize = 1;
-pub const MAX_CUSTOM_TIME: usize = 300; // 5 mins
+pub const MAX_CUSTOM_TIME: usize = 3600; // 1 hour, time tests are endless
 pub const DEFAULT_TIME_MODE_DURATION_IN_SECS: usize = 30; // 30 seconds
 
 pub const MIN_CUSTOM_WORD_COUNT: usize = 1;
 pub const MAX_CUSTOM_WORD_COUNT: usize = 5_000;
 pub const DEFAULT_WORD_MODE_COUNT: usize = 50; // 50 words
 
+pub const DEFAULT_PRACTICE_REPETITIONS: u8 = 3;
+/// Most words of the mistakes pool drilled at once
+pub const MAX_MISTAKES_DRILL_WORDS: usize = 20;
+
 pub const MIN_CUSTOM_LINE_COUNT: usize = 1;
 pub const MAX_CUSTOM_LINE_COUNT: usize = 10;
+/// Column the caret stays at in tape mode
+pub const DEFAULT_TAPE_COLUMN: u16 = 20;
 
 pub const MAX_EXTRA_WRONG_CHARS: usize = 19;
 
+pub const MAX_MIN_WPM: u16 = 350;
+pub const MAX_PACE_CARET_WPM: u16 = 350;
+
 pub const STATE_FILE: &str = "state";
 
 /// Returns the logger file name
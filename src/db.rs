@@ -1,17 +1,24 @@
 use crate::{
     common::filesystem::config_dir,
-    config::{Config, Setting},
-    constants::db_file,
+    config::{Config, ErrorPolicy, Funbox, ModeKind, QuoteLength, Setting},
+    constants::{DEFAULT_LAYOUT, db_file},
     error::{AppError, AppResult},
+    key_stats::{KeyStat, KeyStats, NgramStat, NgramStats},
     log_debug, log_info,
-    tracker::Tracker,
+    replay::Recording,
+    tracker::{CharStats, Tracker},
 };
 use chrono::{DateTime, Local};
 use rusqlite::{Connection, params};
 use serde::{Deserialize, Serialize};
 
-const SCHEMA_VERSION: i32 = 3;
+const SCHEMA_VERSION: i32 = 14;
 const DEFAULT_LEADERBOARD_LIMIT: usize = 25;
+/// Matches the results of the same kind of test: mode, language, word pool, error policy,
+/// whether diacritics were optional, the emulated keyboard layout and the text transforms
+const SAME_TEST_FILTER: &str = "mode_kind = ?1 AND mode_value = ?2 AND language = ?3
+    AND numbers = ?4 AND symbols = ?5 AND punctuation = ?6 AND error_policy = ?7 AND lazy = ?8
+    AND layout = ?9 AND funbox = ?10";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LeaderboardResult {
@@ -22,12 +29,74 @@ pub struct LeaderboardResult {
     pub wpm: u16,
     pub raw_wpm: u16,
     pub accuracy: u16,
+    /// Accuracy of every keystroke, corrected mistakes included
+    pub keystroke_accuracy: u16,
     pub consistency: u16,
     pub error_count: u32,
     pub numbers: bool,
     pub symbols: bool,
     pub punctuation: bool,
+    pub error_policy: String,
     pub created_at: DateTime<Local>,
+    /// Whether the test passed the validity checks. Invalid results are kept but not ranked
+    pub valid: bool,
+    pub char_stats: CharStats,
+    /// Whether diacritics were optional. Lazy results are ranked apart from the strict ones
+    pub lazy: bool,
+    /// Keyboard layout emulated during the test, each layout is ranked on its own
+    pub layout: String,
+    /// Funboxes the text went through, comma separated labels in pipeline order
+    pub funbox: String,
+    /// Keystroke log of the test. Only set when saving, leaderboard queries leave it out
+    #[serde(skip)]
+    pub recording: Option<Recording>,
+    /// Per key statistics of the test. Only set when saving, same as `recording`
+    #[serde(skip)]
+    pub key_stats: Option<KeyStats>,
+    /// Bigram and trigram statistics of the test. Only set when saving, same as `recording`
+    #[serde(skip)]
+    pub ngram_stats: Option<NgramStats>,
+    /// Words left with errors, added to the mistakes pool when saving
+    #[serde(skip)]
+    pub missed_words: Option<Vec<String>>,
+}
+
+impl LeaderboardResult {
+    /// Human readable mode of the result, i.e `Time(30)` or `Quote(short)`.
+    /// Strict error policies, lazy mode, emulated layouts and funboxes are appended, i.e
+    /// `Time(30) [stop-on-word, lazy, dvorak, reverse]`.
+    pub fn mode_display(&self) -> String {
+        let mode = self.base_mode_display();
+        let mut tags = Vec::new();
+        if self.error_policy != ErrorPolicy::Normal.label() {
+            tags.push(self.error_policy.as_str());
+        }
+        if self.lazy {
+            tags.push("lazy");
+        }
+        if self.layout != DEFAULT_LAYOUT {
+            tags.push(self.layout.as_str());
+        }
+        tags.extend(self.funbox.split(',').filter(|funbox| !funbox.is_empty()));
+        if tags.is_empty() {
+            return mode;
+        }
+        format!("{mode} [{}]", tags.join(", "))
+    }
+
+    fn base_mode_display(&self) -> String {
+        if self.mode_kind == ModeKind::Zen.to_display()
+            || self.mode_kind == ModeKind::Code.to_display()
+        {
+            return self.mode_kind.clone();
+        }
+        if self.mode_kind == ModeKind::Quote.to_display()
+            && let Some(length) = QuoteLength::from_value(self.mode_value as usize)
+        {
+            return format!("{}({})", self.mode_kind, length.label());
+        }
+        format!("{}({})", self.mode_kind, self.mode_value)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -43,6 +112,7 @@ pub enum LeaderboardColumn {
     Numbers,
     Symbols,
     Punctuation,
+    ErrorPolicy,
     CreatedAt,
 }
 
@@ -60,6 +130,7 @@ impl LeaderboardColumn {
             LeaderboardColumn::Numbers => "numbers",
             LeaderboardColumn::Symbols => "symbols",
             LeaderboardColumn::Punctuation => "punctuation",
+            LeaderboardColumn::ErrorPolicy => "error_policy",
             LeaderboardColumn::CreatedAt => "created_at",
         }
     }
@@ -93,6 +164,8 @@ pub struct LeaderboardQuery {
     pub offset: usize,
     pub sort_by: LeaderboardColumn, //  TODO: was `sort_col` must be an enum
     pub sort_order: SortOrder,
+    /// Also list the results that failed the validity checks
+    pub include_invalid: bool,
 }
 
 impl Default for LeaderboardQuery {
@@ -102,6 +175,7 @@ impl Default for LeaderboardQuery {
             offset: 0,
             sort_by: LeaderboardColumn::CreatedAt,
             sort_order: SortOrder::Descending,
+            include_invalid: false,
         }
     }
 }
@@ -155,6 +229,7 @@ impl Db {
 
         if current_version < SCHEMA_VERSION {
             self.create()?;
+            self.migrate()?;
             self.conn.execute(
                 "INSERT OR REPLACE INTO schema_version (version) VALUES (?1)",
                 params![SCHEMA_VERSION],
@@ -180,7 +255,62 @@ impl Db {
                 numbers BOOLEAN NOT NULL,
                 punctuation BOOLEAN NOT NULL,
                 symbols BOOLEAN NOT NULL,
-                created_at TEXT NOT NULL
+                error_policy TEXT NOT NULL DEFAULT 'normal',
+                created_at TEXT NOT NULL,
+                valid BOOLEAN NOT NULL DEFAULT 1,
+                keystroke_accuracy INTEGER NOT NULL DEFAULT 0,
+                correct_chars INTEGER NOT NULL DEFAULT 0,
+                incorrect_chars INTEGER NOT NULL DEFAULT 0,
+                corrected_chars INTEGER NOT NULL DEFAULT 0,
+                extra_chars INTEGER NOT NULL DEFAULT 0,
+                missed_chars INTEGER NOT NULL DEFAULT 0,
+                lazy BOOLEAN NOT NULL DEFAULT 0,
+                layout TEXT NOT NULL DEFAULT 'qwerty',
+                funbox TEXT NOT NULL DEFAULT ''
+            )",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS recordings (
+                result_id INTEGER PRIMARY KEY REFERENCES results(id) ON DELETE CASCADE,
+                data TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS key_stats (
+                result_id INTEGER NOT NULL REFERENCES results(id) ON DELETE CASCADE,
+                key TEXT NOT NULL,
+                attempts INTEGER NOT NULL,
+                misses INTEGER NOT NULL,
+                latency_ms INTEGER NOT NULL,
+                latency_samples INTEGER NOT NULL,
+                substitutions TEXT NOT NULL,
+                PRIMARY KEY (result_id, key)
+            )",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS ngram_stats (
+                result_id INTEGER NOT NULL REFERENCES results(id) ON DELETE CASCADE,
+                ngram TEXT NOT NULL,
+                occurrences INTEGER NOT NULL,
+                misses INTEGER NOT NULL,
+                latency_ms INTEGER NOT NULL,
+                PRIMARY KEY (result_id, ngram)
+            )",
+            [],
+        )?;
+
+        // words the user keeps getting wrong, across every test
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS mistakes (
+                word TEXT PRIMARY KEY,
+                misses INTEGER NOT NULL,
+                last_missed_at TEXT NOT NULL
             )",
             [],
         )?;
@@ -191,14 +321,62 @@ impl Db {
         Ok(())
     }
 
-    fn create_indexes(&mut self) -> AppResult<()> {
+    /// Brings tables created by older schema versions up to date.
+    fn migrate(&mut self) -> AppResult<()> {
+        // v4: results are split by error policy
+        self.add_column_if_missing("results", "error_policy", "TEXT NOT NULL DEFAULT 'normal'")?;
+        // v9: results that fail the validity checks are kept but not ranked
+        self.add_column_if_missing("results", "valid", "BOOLEAN NOT NULL DEFAULT 1")?;
+        // v10: lifetime keystroke counters, older results have none
+        for column in [
+            "keystroke_accuracy",
+            "correct_chars",
+            "incorrect_chars",
+            "corrected_chars",
+            "extra_chars",
+            "missed_chars",
+        ] {
+            self.add_column_if_missing("results", column, "INTEGER NOT NULL DEFAULT 0")?;
+        }
+        // v11: lazy results are tracked apart
+        self.add_column_if_missing("results", "lazy", "BOOLEAN NOT NULL DEFAULT 0")?;
+        // v12: results remember the emulated keyboard layout
+        self.add_column_if_missing("results", "layout", "TEXT NOT NULL DEFAULT 'qwerty'")?;
+        // v13: results remember the funboxes the text went through
+        self.add_column_if_missing("results", "funbox", "TEXT NOT NULL DEFAULT ''")?;
+        // v14: the ranking index covers every column of `SAME_TEST_FILTER`. Recreated on every
+        // schema bump so the columns added above make it in
+        self.conn.execute("DROP INDEX IF EXISTS idx_filters", [])?;
         self.conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_filters ON results (
-                mode_kind, mode_value, language, numbers, punctuation, symbols
+            "CREATE INDEX idx_filters ON results (
+                mode_kind, mode_value, language, numbers, symbols, punctuation, error_policy,
+                lazy, layout, funbox
             )",
             [],
         )?;
+        Ok(())
+    }
+
+    fn add_column_if_missing(&self, table: &str, column: &str, definition: &str) -> AppResult<()> {
+        let mut statement = self.conn.prepare(&format!("PRAGMA table_info({table})"))?;
+        let exists = statement
+            .query_map([], |row| row.get::<_, String>(1))?
+            .filter_map(Result::ok)
+            .any(|name| name == column);
 
+        if !exists {
+            self.conn.execute(
+                &format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"),
+                [],
+            )?;
+            log_info!("DB: added column '{column}' to '{table}'");
+        }
+
+        Ok(())
+    }
+
+    /// Indexes over columns every schema version has. `idx_filters` is built by `migrate`
+    fn create_indexes(&mut self) -> AppResult<()> {
         self.conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_wpm ON results (wpm DESC)",
             [],
@@ -230,12 +408,23 @@ impl Db {
             wpm: summary.wpm.round() as u16,
             raw_wpm: summary.raw_wpm().round() as u16,
             accuracy: (summary.accuracy * 100.0) as u16,
+            keystroke_accuracy: (summary.keystroke_accuracy * 100.0) as u16,
             consistency: summary.consistency as u16,
             error_count: summary.total_errors as u32,
             numbers: config.is_enabled(Setting::Numbers),
             symbols: config.is_enabled(Setting::Symbols),
             punctuation: config.is_enabled(Setting::Punctuation),
+            error_policy: config.current_error_policy().label().to_string(),
             created_at: Local::now(),
+            valid: summary.validity.is_valid(),
+            char_stats: summary.char_stats,
+            lazy: config.is_enabled(Setting::Lazy),
+            layout: config.current_layout(),
+            funbox: Funbox::join_labels(config.current_funbox()),
+            recording: Some(Recording::from_tracker(tracker)),
+            key_stats: Some(KeyStats::from_tracker(tracker)),
+            ngram_stats: Some(NgramStats::from_tracker(tracker)),
+            missed_words: Some(tracker.missed_words()),
         }
     }
 
@@ -245,7 +434,8 @@ impl Db {
     }
 
     pub fn write_result(&mut self, result: LeaderboardResult) -> AppResult<i64> {
-        self.conn.execute(
+        let tx = self.conn.transaction()?;
+        tx.execute(
             "INSERT INTO results (
                 mode_kind,
                 mode_value,
@@ -258,8 +448,22 @@ impl Db {
                 numbers,
                 symbols,
                 punctuation,
-                created_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                error_policy,
+                created_at,
+                valid,
+                keystroke_accuracy,
+                correct_chars,
+                incorrect_chars,
+                corrected_chars,
+                extra_chars,
+                missed_chars,
+                lazy,
+                layout,
+                funbox
+            ) VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18,
+                ?19, ?20, ?21, ?22, ?23
+            )",
             params![
                 result.mode_kind,
                 result.mode_value,
@@ -272,11 +476,77 @@ impl Db {
                 result.numbers,
                 result.symbols,
                 result.punctuation,
-                result.created_at
+                result.error_policy,
+                result.created_at,
+                result.valid,
+                result.keystroke_accuracy,
+                result.char_stats.correct,
+                result.char_stats.incorrect,
+                result.char_stats.corrected,
+                result.char_stats.extra,
+                result.char_stats.missed,
+                result.lazy,
+                result.layout,
+                result.funbox
             ],
         )?;
 
-        let id = self.conn.last_insert_rowid();
+        let id = tx.last_insert_rowid();
+
+        if let Some(recording) = &result.recording {
+            tx.execute(
+                "INSERT INTO recordings (result_id, data) VALUES (?1, ?2)",
+                params![id, serde_json::to_string(recording)?],
+            )?;
+        }
+        if let Some(key_stats) = &result.key_stats {
+            let mut statement = tx.prepare(
+                "INSERT INTO key_stats (
+                    result_id, key, attempts, misses, latency_ms, latency_samples, substitutions
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            for (key, stat) in key_stats.iter() {
+                statement.execute(params![
+                    id,
+                    key.to_string(),
+                    stat.attempts,
+                    stat.misses,
+                    stat.latency_ms,
+                    stat.latency_samples,
+                    serde_json::to_string(&stat.substitutions)?,
+                ])?;
+            }
+        }
+        if let Some(ngram_stats) = &result.ngram_stats {
+            let mut statement = tx.prepare(
+                "INSERT INTO ngram_stats (result_id, ngram, occurrences, misses, latency_ms)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for (ngram, stat) in ngram_stats.iter() {
+                statement.execute(params![
+                    id,
+                    ngram,
+                    stat.occurrences,
+                    stat.misses,
+                    stat.latency_ms,
+                ])?;
+            }
+        }
+        // the words of a test that wasn't really typed are no mistakes worth practicing
+        if result.valid
+            && let Some(missed_words) = &result.missed_words
+        {
+            let mut statement = tx.prepare(
+                "INSERT INTO mistakes (word, misses, last_missed_at) VALUES (?1, 1, ?2)
+                 ON CONFLICT(word) DO UPDATE SET
+                    misses = misses + 1,
+                    last_missed_at = excluded.last_missed_at",
+            )?;
+            for word in missed_words {
+                statement.execute(params![word, result.created_at])?;
+            }
+        }
+        tx.commit()?;
 
         log_debug!("DB: saved test result to database with ID: '{id}'");
 
@@ -285,6 +555,7 @@ impl Db {
 
     pub fn reset(&self) -> AppResult<usize> {
         let affected_rows = self.conn.execute("DELETE FROM results", [])?;
+        self.conn.execute("DELETE FROM mistakes", [])?;
         log_info!("DB: reset database, deleted {affected_rows} results");
         Ok(affected_rows)
     }
@@ -319,9 +590,16 @@ impl Db {
         }
         let sort_direction = query.sort_order.to_value();
         let sort_col = query.sort_by.to_value();
-        let count: usize = self
-            .conn
-            .query_row("SELECT COUNT(*) FROM results", [], |row| row.get(0))?;
+        let filter = if query.include_invalid {
+            ""
+        } else {
+            "WHERE valid = 1"
+        };
+        let count: usize = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM results {filter}"),
+            [],
+            |row| row.get(0),
+        )?;
 
         let sql_payload = format!(
             "SELECT
@@ -337,18 +615,30 @@ impl Db {
                 numbers,
                 symbols,
                 punctuation,
-                created_at
+                error_policy,
+                created_at,
+                valid,
+                keystroke_accuracy,
+                correct_chars,
+                incorrect_chars,
+                corrected_chars,
+                extra_chars,
+                missed_chars,
+                lazy,
+                layout,
+                funbox
               FROM results
+             {}
              ORDER BY {} {}
              LIMIT {} OFFSET {}",
-            sort_col, sort_direction, query.limit, query.offset
+            filter, sort_col, sort_direction, query.limit, query.offset
         );
 
         let mut statement = self.conn.prepare(&sql_payload)?;
 
         let results: Result<Vec<LeaderboardResult>, rusqlite::Error> = statement
             .query_map([], |row| {
-                let created_at: DateTime<Local> = row.get(12)?;
+                let created_at: DateTime<Local> = row.get(13)?;
 
                 Ok(LeaderboardResult {
                     id: Some(row.get(0)?),
@@ -363,7 +653,24 @@ impl Db {
                     numbers: row.get(9)?,
                     symbols: row.get(10)?,
                     punctuation: row.get(11)?,
+                    error_policy: row.get(12)?,
                     created_at,
+                    valid: row.get(14)?,
+                    keystroke_accuracy: row.get(15)?,
+                    char_stats: CharStats {
+                        correct: row.get(16)?,
+                        incorrect: row.get(17)?,
+                        corrected: row.get(18)?,
+                        extra: row.get(19)?,
+                        missed: row.get(20)?,
+                    },
+                    lazy: row.get(21)?,
+                    layout: row.get(22)?,
+                    funbox: row.get(23)?,
+                    recording: None,
+                    key_stats: None,
+                    ngram_stats: None,
+                    missed_words: None,
                 })
             })?
             .collect();
@@ -377,6 +684,156 @@ impl Db {
         })
     }
 
+    /// The keystroke recording saved with the given result, if any
+    pub fn recording(&self, result_id: i64) -> AppResult<Option<Recording>> {
+        let data = self.conn.query_row(
+            "SELECT data FROM recordings WHERE result_id = ?1",
+            params![result_id],
+            |row| row.get::<_, String>(0),
+        );
+        match data {
+            Ok(data) => Ok(Some(serde_json::from_str(&data)?)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Per key statistics of every valid saved test added up
+    pub fn all_time_key_stats(&self) -> AppResult<KeyStats> {
+        let mut statement = self.conn.prepare(
+            "SELECT key, attempts, misses, latency_ms, latency_samples, substitutions
+             FROM key_stats JOIN results ON results.id = key_stats.result_id
+             WHERE results.valid = 1",
+        )?;
+        let rows = statement.query_map([], |row| {
+            let stat = KeyStat {
+                attempts: row.get(1)?,
+                misses: row.get(2)?,
+                latency_ms: row.get(3)?,
+                latency_samples: row.get(4)?,
+                substitutions: Default::default(),
+            };
+            Ok((row.get::<_, String>(0)?, stat, row.get::<_, String>(5)?))
+        })?;
+
+        let mut stats = KeyStats::default();
+        for row in rows {
+            let (key, mut stat, substitutions) = row?;
+            let Some(key) = key.chars().next() else {
+                continue;
+            };
+            stat.substitutions = serde_json::from_str(&substitutions)?;
+            stats.add(key, &stat);
+        }
+        Ok(stats)
+    }
+
+    /// Words of the mistakes pool, the most missed and then the most recently missed first
+    pub fn mistakes(&self, limit: usize) -> AppResult<Vec<String>> {
+        let mut statement = self.conn.prepare(
+            "SELECT word FROM mistakes ORDER BY misses DESC, last_missed_at DESC LIMIT ?1",
+        )?;
+        let words = statement
+            .query_map(params![limit as i64], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(words)
+    }
+
+    /// Takes the given words out of the mistakes pool, i.e once they were practiced without errors
+    pub fn resolve_mistakes(&self, words: &[String]) -> AppResult<usize> {
+        let mut statement = self.conn.prepare("DELETE FROM mistakes WHERE word = ?1")?;
+        let mut resolved = 0;
+        for word in words {
+            resolved += statement.execute(params![word])?;
+        }
+        Ok(resolved)
+    }
+
+    /// Bigram and trigram statistics of every valid saved test added up
+    pub fn all_time_ngram_stats(&self) -> AppResult<NgramStats> {
+        let mut statement = self.conn.prepare(
+            "SELECT ngram, SUM(occurrences), SUM(misses), SUM(latency_ms)
+             FROM ngram_stats JOIN results ON results.id = ngram_stats.result_id
+             WHERE results.valid = 1 GROUP BY ngram",
+        )?;
+        let rows = statement.query_map([], |row| {
+            let stat = NgramStat {
+                occurrences: row.get(1)?,
+                misses: row.get(2)?,
+                latency_ms: row.get(3)?,
+            };
+            Ok((row.get::<_, String>(0)?, stat))
+        })?;
+
+        let mut stats = NgramStats::default();
+        for row in rows {
+            let (ngram, stat) = row?;
+            stats.add(&ngram, &stat);
+        }
+        Ok(stats)
+    }
+
+    /// The fastest saved run of the same kind of test as the current config that has a recording,
+    /// along with its WPM
+    pub fn best_recording(&self, config: &Config) -> AppResult<Option<(u16, Recording)>> {
+        let mode = config.current_mode();
+        let best = self.conn.query_row(
+            &format!(
+                "SELECT wpm, data FROM results JOIN recordings ON recordings.result_id = results.id
+                 WHERE {SAME_TEST_FILTER} AND valid = 1 ORDER BY wpm DESC LIMIT 1"
+            ),
+            params![
+                mode.kind().to_display(),
+                mode.value() as i32,
+                config.current_language(),
+                config.is_enabled(Setting::Numbers),
+                config.is_enabled(Setting::Symbols),
+                config.is_enabled(Setting::Punctuation),
+                config.current_error_policy().label(),
+                config.is_enabled(Setting::Lazy),
+                config.current_layout(),
+                Funbox::join_labels(config.current_funbox()),
+            ],
+            |row| Ok((row.get::<_, f64>(0)?, row.get::<_, String>(1)?)),
+        );
+        match best {
+            Ok((wpm, data)) => Ok(Some((wpm.round() as u16, serde_json::from_str(&data)?))),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Average WPM of the valid saved results of the same kind of test as the current config
+    pub fn average_wpm(&self, config: &Config) -> AppResult<Option<f64>> {
+        self.aggregate_wpm(config, "AVG")
+    }
+
+    /// Personal best WPM, among valid results, of the same kind of test as the current config
+    pub fn best_wpm(&self, config: &Config) -> AppResult<Option<f64>> {
+        self.aggregate_wpm(config, "MAX")
+    }
+
+    fn aggregate_wpm(&self, config: &Config, aggregate: &str) -> AppResult<Option<f64>> {
+        let mode = config.current_mode();
+        let wpm = self.conn.query_row(
+            &format!("SELECT {aggregate}(wpm) FROM results WHERE {SAME_TEST_FILTER} AND valid = 1"),
+            params![
+                mode.kind().to_display(),
+                mode.value() as i32,
+                config.current_language(),
+                config.is_enabled(Setting::Numbers),
+                config.is_enabled(Setting::Symbols),
+                config.is_enabled(Setting::Punctuation),
+                config.current_error_policy().label(),
+                config.is_enabled(Setting::Lazy),
+                config.current_layout(),
+                Funbox::join_labels(config.current_funbox()),
+            ],
+            |row| row.get::<_, Option<f64>>(0),
+        )?;
+        Ok(wpm)
+    }
+
     fn is_valid_column(&self, column: &LeaderboardColumn) -> bool {
         let col = column.to_value();
         let valid_cols = [
@@ -391,6 +848,7 @@ impl Db {
             "numbers",
             "punctuation",
             "symbols",
+            "error_policy",
             "created_at",
         ];
 
@@ -418,6 +876,14 @@ mod tests {
         Db::new_in_memory().expect("Failed to create test database")
     }
 
+    /// Saves the test as a ranked result. Tests typed in no time with a word or two are too short
+    /// and too fast to be valid, but these tests are about what gets saved along with them
+    fn write_valid_result(db: &mut Db, config: &Config, tracker: &Tracker) {
+        let mut result = Db::build_result(config, tracker);
+        result.valid = true;
+        db.write_result(result).unwrap();
+    }
+
     fn insert_test_result(
         db: &mut Db,
         mode_kind: &str,
@@ -513,4 +979,322 @@ mod tests {
         assert_eq!(state2.data.len(), 2);
         assert!(state2.has_more);
     }
+
+    #[test]
+    fn test_save_quote_results() {
+        let mut db = create_test_db();
+        let mut config = Config::default();
+        config.change_mode(Mode::Quote(QuoteLength::Short)).unwrap();
+        let mut tracker = Tracker::new("test".to_string(), Mode::Quote(QuoteLength::Short));
+        tracker.start_typing();
+        for c in "test".chars() {
+            tracker.type_char(c).unwrap()
+        }
+
+        tracker.complete();
+
+        let result = Db::build_result(&config, &tracker);
+        assert_eq!(result.mode_kind, "Quote");
+        assert_eq!(result.mode_display(), "Quote(short)");
+
+        db.write_result(result).unwrap();
+        // a single word quote is too short to be ranked, but it's still saved
+        let query = LeaderboardQuery {
+            include_invalid: true,
+            ..Default::default()
+        };
+        let state = db.query_data(&query).unwrap();
+        assert_eq!(state.data[0].mode_kind, "Quote");
+        assert!(!state.data[0].valid);
+        assert_eq!(state.data[0].mode_value, QuoteLength::Short.value() as i32);
+    }
+
+    #[test]
+    fn test_save_error_policy_results() {
+        let mut db = create_test_db();
+        let mut config = Config::default();
+        config.change_error_policy(ErrorPolicy::StopOnWord);
+        let mut tracker = Tracker::new("test".to_string(), Mode::with_time(30));
+        tracker.start_typing();
+        for c in "test".chars() {
+            tracker.type_char(c).unwrap()
+        }
+
+        tracker.complete();
+
+        let result = Db::build_result(&config, &tracker);
+        assert_eq!(result.mode_display(), "Time(30) [stop-on-word]");
+
+        db.write_result(result).unwrap();
+        insert_test_result(&mut db, "Time", 30, "english", 80, 95);
+        let query = LeaderboardQuery {
+            sort_by: LeaderboardColumn::ErrorPolicy,
+            sort_order: SortOrder::Ascending,
+            ..Default::default()
+        };
+        let state = db.query_data(&query).unwrap();
+        assert_eq!(state.data[0].error_policy, "normal");
+        assert_eq!(state.data[0].mode_display(), "Time(30)");
+        assert_eq!(state.data[1].error_policy, "stop-on-word");
+    }
+
+    #[test]
+    fn test_migrate_adds_missing_columns() {
+        let mut db = Db {
+            conn: Connection::open_in_memory().unwrap(),
+        };
+        db.conn
+            .execute_batch(
+                "CREATE TABLE schema_version (version INTEGER PRIMARY KEY);
+                 INSERT INTO schema_version (version) VALUES (3);
+                 CREATE TABLE results (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    mode_kind TEXT NOT NULL,
+                    mode_value INTEGER NOT NULL,
+                    language TEXT NOT NULL,
+                    wpm REAL NOT NULL,
+                    raw_wpm REAL DEFAULT 0,
+                    accuracy INTEGER NOT NULL,
+                    consistency INTEGER NOT NULL,
+                    error_count INTEGER NOT NULL,
+                    numbers BOOLEAN NOT NULL,
+                    punctuation BOOLEAN NOT NULL,
+                    symbols BOOLEAN NOT NULL,
+                    created_at TEXT NOT NULL
+                 );",
+            )
+            .unwrap();
+        insert_test_result(&mut db, "Time", 30, "english", 80, 95);
+
+        db.init().unwrap();
+
+        let state = db.query_data(&LeaderboardQuery::default()).unwrap();
+        assert_eq!(state.data[0].error_policy, "normal");
+        assert_eq!(db.recording(state.data[0].id.unwrap()).unwrap(), None);
+
+        // the ranking index covers every ranking column, even the ones added by migrations
+        let mut statement = db.conn.prepare("PRAGMA index_info(idx_filters)").unwrap();
+        let columns: Vec<String> = statement
+            .query_map([], |row| row.get(2))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        for column in ["error_policy", "lazy", "layout", "funbox"] {
+            assert!(columns.iter().any(|c| c == column), "{column}");
+        }
+    }
+
+    #[test]
+    fn test_save_recording() {
+        let mut db = create_test_db();
+        let config = Config::default();
+        let mut tracker = Tracker::new("test".to_string(), Mode::with_words(1));
+        for c in "tx\x08est".chars() {
+            match c {
+                '\x08' => tracker.backspace().unwrap(),
+                c => tracker.type_char(c).unwrap(),
+            }
+        }
+        assert!(tracker.check_completion());
+
+        let id = db.write(&config, &tracker).unwrap();
+        let recording = db.recording(id).unwrap().unwrap();
+        assert_eq!(recording, Recording::from_tracker(&tracker));
+        assert_eq!(recording.keystrokes.len(), 6);
+
+        // recordings go away with their result
+        db.reset().unwrap();
+        assert_eq!(db.recording(id).unwrap(), None);
+    }
+
+    #[test]
+    fn test_all_time_key_stats() {
+        let mut db = create_test_db();
+        let config = Config::default();
+        assert!(db.all_time_key_stats().unwrap().is_empty());
+
+        let mut tracker = Tracker::new("ab".to_string(), Mode::with_words(1));
+        for c in "xb".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        write_valid_result(&mut db, &config, &tracker);
+        write_valid_result(&mut db, &config, &tracker);
+
+        let stats = db.all_time_key_stats().unwrap();
+        let a = stats.get('a').unwrap();
+        assert_eq!(a.attempts, 2);
+        assert_eq!(a.misses, 2);
+        assert_eq!(a.top_substitution(), Some('x'));
+        assert_eq!(stats.get('b').unwrap().misses, 0);
+
+        // key stats go away with their result
+        db.reset().unwrap();
+        assert!(db.all_time_key_stats().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_all_time_ngram_stats() {
+        let mut db = create_test_db();
+        let config = Config::default();
+        assert!(db.all_time_ngram_stats().unwrap().is_empty());
+
+        let mut tracker = Tracker::new("the".to_string(), Mode::with_words(1));
+        for c in "thx".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        write_valid_result(&mut db, &config, &tracker);
+        write_valid_result(&mut db, &config, &tracker);
+
+        let stats = db.all_time_ngram_stats().unwrap();
+        assert_eq!(stats.get("th").unwrap().occurrences, 2);
+        assert_eq!(stats.get("th").unwrap().misses, 0);
+        assert_eq!(stats.get("the").unwrap().misses, 2);
+    }
+
+    #[test]
+    fn test_mistakes_pool() {
+        let mut db = create_test_db();
+        let config = Config::default();
+        assert!(db.mistakes(10).unwrap().is_empty());
+
+        let mut tracker = Tracker::new("ab cd ef".to_string(), Mode::with_words(3));
+        for c in "ax cx ef".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        write_valid_result(&mut db, &config, &tracker);
+
+        let mut tracker = Tracker::new("cd".to_string(), Mode::with_words(1));
+        tracker.type_char('x').unwrap();
+        write_valid_result(&mut db, &config, &tracker);
+
+        // `cd` was missed twice
+        assert_eq!(db.mistakes(10).unwrap(), vec!["cd", "ab"]);
+        assert_eq!(db.mistakes(1).unwrap(), vec!["cd"]);
+
+        assert_eq!(db.resolve_mistakes(&["cd".to_string()]).unwrap(), 1);
+        assert_eq!(db.mistakes(10).unwrap(), vec!["ab"]);
+    }
+
+    #[test]
+    fn test_best_recording() {
+        let mut db = create_test_db();
+        let config = Config::default();
+        assert!(db.best_recording(&config).unwrap().is_none());
+
+        let tracker = Tracker::new("test".to_string(), config.current_mode());
+        let mut slow = Db::build_result(&config, &tracker);
+        slow.wpm = 40;
+        let mut fast = slow.clone();
+        fast.wpm = 90;
+        // the fastest run has no recording, it can't be raced
+        let mut unrecorded = slow.clone();
+        unrecorded.wpm = 120;
+        unrecorded.recording = None;
+        db.write_result(slow).unwrap();
+        db.write_result(fast.clone()).unwrap();
+        db.write_result(unrecorded).unwrap();
+
+        let (wpm, recording) = db.best_recording(&config).unwrap().unwrap();
+        assert_eq!(wpm, 90);
+        assert_eq!(Some(recording), fast.recording);
+    }
+
+    #[test]
+    fn test_average_and_best_wpm() {
+        let mut db = create_test_db();
+        let config = Config::default();
+        assert_eq!(db.best_wpm(&config).unwrap(), None);
+
+        let mode = config.current_mode();
+        let kind = mode.kind().to_display();
+        let language = config.current_language();
+        insert_test_result(&mut db, &kind, mode.value() as i32, &language, 60, 95);
+        insert_test_result(&mut db, &kind, mode.value() as i32, &language, 80, 95);
+        // other modes don't count
+        insert_test_result(&mut db, &kind, 999, &language, 200, 95);
+
+        assert_eq!(db.average_wpm(&config).unwrap(), Some(70.0));
+        assert_eq!(db.best_wpm(&config).unwrap(), Some(80.0));
+    }
+
+    #[test]
+    fn test_invalid_results_are_not_ranked() {
+        let mut db = create_test_db();
+        let config = Config::default();
+        let tracker = Tracker::new("test".to_string(), config.current_mode());
+        let mut valid = Db::build_result(&config, &tracker);
+        valid.wpm = 60;
+        valid.missed_words = Some(vec!["test".to_string()]);
+        let mut invalid = valid.clone();
+        invalid.wpm = 250;
+        invalid.valid = false;
+        invalid.missed_words = Some(vec!["pasted".to_string()]);
+        db.write_result(valid).unwrap();
+        db.write_result(invalid).unwrap();
+
+        assert_eq!(db.best_wpm(&config).unwrap(), Some(60.0));
+        assert_eq!(db.best_recording(&config).unwrap().unwrap().0, 60);
+        assert_eq!(db.mistakes(10).unwrap(), vec!["test"]);
+
+        let state = db.query_data(&LeaderboardQuery::default()).unwrap();
+        assert_eq!(state.count, 1);
+        assert!(state.data.iter().all(|r| r.valid));
+
+        let query = LeaderboardQuery {
+            include_invalid: true,
+            ..Default::default()
+        };
+        let state = db.query_data(&query).unwrap();
+        assert_eq!(state.count, 2);
+        assert!(state.data.iter().any(|r| !r.valid));
+    }
+
+    #[test]
+    fn test_results_are_ranked_apart_per_setting() {
+        type Change = fn(&mut Config);
+        // each case changes one ranking column, along with the tag it adds to the mode
+        let cases: [(Change, &str); 3] = [
+            (
+                |config| config.toggle(&Setting::Lazy).unwrap(),
+                "Time(30) [lazy]",
+            ),
+            (
+                |config| config.change_layout("dvorak".to_string()),
+                "Time(30) [dvorak]",
+            ),
+            (
+                |config| {
+                    config.toggle(&Setting::Funbox(Funbox::RandomCase)).unwrap();
+                    config.toggle(&Setting::Funbox(Funbox::Reverse)).unwrap();
+                },
+                "Time(30) [reverse, random-case]",
+            ),
+        ];
+
+        for (change, mode_display) in cases {
+            let mut db = create_test_db();
+            let mut config = Config::default();
+            let tracker = Tracker::new("test".to_string(), config.current_mode());
+            let mut plain = Db::build_result(&config, &tracker);
+            plain.wpm = 90;
+            assert_eq!(plain.mode_display(), "Time(30)");
+            db.write_result(plain).unwrap();
+
+            change(&mut config);
+            let mut changed = Db::build_result(&config, &tracker);
+            changed.wpm = 40;
+            assert_eq!(changed.mode_display(), mode_display);
+            db.write_result(changed).unwrap();
+
+            assert_eq!(db.best_wpm(&config).unwrap(), Some(40.0), "{mode_display}");
+            let plain_config = Config::default();
+            assert_eq!(
+                db.best_wpm(&plain_config).unwrap(),
+                Some(90.0),
+                "{mode_display}"
+            );
+            let state = db.query_data(&LeaderboardQuery::default()).unwrap();
+            assert_eq!(state.data.len(), 2);
+        }
+    }
 }
@@ -12,13 +12,14 @@ use std::{
 const WRITER_CHANNEL_CAPACITY: usize = 256;
 
 enum WriteMessage {
-    Save(LeaderboardResult),
+    Save(Box<LeaderboardResult>),
     Shutdown,
 }
 
+/// The rejected result is handed back so it can still be written synchronously
 pub enum EnqueueError {
-    Full(LeaderboardResult),
-    Disconnected(LeaderboardResult),
+    Full(Box<LeaderboardResult>),
+    Disconnected(Box<LeaderboardResult>),
 }
 
 pub struct DbWriter {
@@ -48,7 +49,7 @@ impl DbWriter {
             loop {
                 match receiver.recv_timeout(Duration::from_millis(200)) {
                     Ok(WriteMessage::Save(result)) => {
-                        if let Err(err) = db.write_result(result) {
+                        if let Err(err) = db.write_result(*result) {
                             log_error!("DB writer: failed writing result: {err}");
                         }
                     }
@@ -66,7 +67,7 @@ impl DbWriter {
     }
 
     pub fn enqueue(&self, result: LeaderboardResult) -> Result<(), EnqueueError> {
-        match self.sender.try_send(WriteMessage::Save(result)) {
+        match self.sender.try_send(WriteMessage::Save(Box::new(result))) {
             Ok(()) => Ok(()),
             Err(TrySendError::Full(WriteMessage::Save(result))) => Err(EnqueueError::Full(result)),
             Err(TrySendError::Disconnected(WriteMessage::Save(result))) => {
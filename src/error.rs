@@ -14,6 +14,7 @@ pub enum AppError {
     IllegalSpaceCharacter,
     TermiDB(String),
     InvalidLanguage(String),
+    InvalidLayout(String),
     SqliteError(rusqlite::Error),
     Other(String),
 }
@@ -31,6 +32,7 @@ impl std::fmt::Display for AppError {
             Self::IllegalSpaceCharacter => write!(f, "Cannot type <Space> at current location"),
             Self::InvalidConfigData(msg) => write!(f, "Invalid configuration data: {msg}"),
             Self::InvalidLanguage(lang) => write!(f, "Invalid language: {lang}"),
+            Self::InvalidLayout(msg) => write!(f, "Invalid keyboard layout: {msg}"),
             Self::TermiDB(err) => write!(f, "TermiDB Error: {err}"),
             Self::SqliteError(err) => write!(f, "Sqlite Error: {err}"),
             Self::Other(err) => write!(f, "Error: {err}"),
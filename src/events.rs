@@ -0,0 +1,120 @@
+use crate::tracker::{FailureReason, Tracker};
+
+/// Something that happened in a typing test. The tracker queues these as its state changes and
+/// the app hands them out once per loop iteration
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrackerEvent {
+    /// The first keystroke started the test
+    TestStarted,
+    /// A character reached the text, extra characters past the end of a word included
+    CharTyped {
+        c: char,
+        correct: bool,
+    },
+    /// A character was swallowed by the error policy
+    CharRejected(char),
+    /// The last typed character was taken back
+    Backspace,
+    /// The current word, or the previous one at a word boundary, was taken back
+    WordDeleted,
+    /// The word at the given index was submitted
+    WordCompleted {
+        index: usize,
+    },
+    /// The rest of the current word was skipped by typing a separator early
+    SpaceJump {
+        skipped: usize,
+    },
+    Paused,
+    Resumed,
+    /// More words were appended to the end of the text
+    TextExtended,
+    /// The test was reset, a whole new text might be in place
+    Reset,
+    Completed,
+    Failed(FailureReason),
+}
+
+impl TrackerEvent {
+    /// Whether the event changed what the text looks like
+    pub fn changes_text(&self) -> bool {
+        !matches!(
+            self,
+            Self::TestStarted | Self::CharRejected(_) | Self::Paused | Self::Resumed
+        )
+    }
+
+    /// Whether the test is over after this event
+    pub fn ends_test(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed(_))
+    }
+}
+
+/// Anything that wants to react to a typing test as it happens, i.e sounds, hooks or analytics.
+/// Events are delivered in order but after the fact, `tracker` is the state after the whole
+/// batch of events the observer is being handed
+pub trait TrackerObserver {
+    fn on_event(&mut self, event: &TrackerEvent, tracker: &Tracker);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Mode;
+
+    #[test]
+    fn test_tracker_emits_events() {
+        let mut tracker = Tracker::new("ab cd ef".to_string(), Mode::with_words(3));
+        for c in "ax c".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        tracker.backspace().unwrap();
+        tracker.pause();
+
+        assert_eq!(
+            tracker.take_events(),
+            vec![
+                TrackerEvent::TestStarted,
+                TrackerEvent::CharTyped {
+                    c: 'a',
+                    correct: true
+                },
+                TrackerEvent::CharTyped {
+                    c: 'x',
+                    correct: false
+                },
+                TrackerEvent::CharTyped {
+                    c: ' ',
+                    correct: true
+                },
+                TrackerEvent::WordCompleted { index: 0 },
+                TrackerEvent::CharTyped {
+                    c: 'c',
+                    correct: true
+                },
+                TrackerEvent::Backspace,
+                TrackerEvent::Paused,
+            ]
+        );
+        // events are handed out once
+        assert!(tracker.take_events().is_empty());
+
+        tracker.reset("ab".to_string(), Mode::with_words(1));
+        assert_eq!(tracker.take_events(), vec![TrackerEvent::Reset]);
+    }
+
+    #[test]
+    fn test_space_jump_and_completion_events() {
+        let mut tracker = Tracker::new("ab cd".to_string(), Mode::with_words(2));
+        for c in "a cd".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert!(tracker.check_completion());
+
+        let events = tracker.take_events();
+        assert!(events.contains(&TrackerEvent::SpaceJump { skipped: 1 }));
+        assert!(events.contains(&TrackerEvent::WordCompleted { index: 0 }));
+        assert_eq!(events.last(), Some(&TrackerEvent::Completed));
+        assert!(events.last().unwrap().ends_test());
+    }
+}
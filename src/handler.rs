@@ -2,13 +2,15 @@ use crate::{
     actions::{self},
     app::App,
     ascii,
-    config::{self, Mode, Setting},
+    config::{self, Difficulty, ErrorPolicy, Mode, PaceCaret, QuoteLength, Setting},
     error::AppError,
+    key_stats::NgramStats,
+    layout::KeyboardLayout,
     leaderboard::{Leaderboard, LeaderboardMotion, SortColumn},
     log_warn,
     menu::{MenuContext, MenuMotion},
     modal::{Modal, ModalContext},
-    notify_info, theme,
+    notify_error, notify_info, theme,
     variants::{CursorVariant, PickerVariant, ResultsVariant},
 };
 
@@ -17,15 +19,11 @@ pub struct AppHandler;
 
 impl AppHandler {
     pub fn handle_input(self, app: &mut App, chr: char) -> Result<(), AppError> {
-        if app.tracker.is_complete() {
+        if app.tracker.is_complete() || app.is_replaying() {
             return Ok(());
         }
         match app.tracker.type_char(chr) {
-            Ok(()) => {
-                app.bump_typing_revision();
-                Ok(())
-            }
-            Err(AppError::IllegalSpaceCharacter) => Ok(()),
+            Ok(()) | Err(AppError::IllegalSpaceCharacter) => Ok(()),
             Err(e) => Err(e),
         }
     }
@@ -242,6 +240,30 @@ impl AppHandler {
                         app.restart()?
                     }
                 }
+                ModalContext::MinAccuracy => {
+                    if let Ok(val) = modal.get_value()
+                        && let Ok(accuracy) = val.parse::<u8>()
+                    {
+                        app.config.change_min_accuracy(accuracy);
+                        app.restart()?
+                    }
+                }
+                ModalContext::MinWpm => {
+                    if let Ok(val) = modal.get_value()
+                        && let Ok(wpm) = val.parse::<u16>()
+                    {
+                        app.config.change_min_wpm(wpm);
+                        app.restart()?
+                    }
+                }
+                ModalContext::PaceCaretWpm => {
+                    if let Ok(val) = modal.get_value()
+                        && let Ok(wpm) = val.parse::<u16>()
+                    {
+                        app.config.change_pace_caret(PaceCaret::Fixed(wpm));
+                        app.restart()?
+                    }
+                }
                 ModalContext::ExitConfirmation => app.quit()?,
             }
         }
@@ -251,11 +273,24 @@ impl AppHandler {
     }
 
     pub fn handle_backspace(self, app: &mut App) -> Result<(), AppError> {
+        if app.is_replaying() {
+            return Ok(());
+        }
         match app.tracker.backspace() {
-            Ok(()) => {
-                app.bump_typing_revision();
-                Ok(())
-            }
+            Ok(()) => Ok(()),
+            Err(AppError::TypingTestNotInProgress) => Ok(()),
+            Err(AppError::IllegalBackspace) => Ok(()),
+            Err(AppError::IllegalSpaceCharacter) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn handle_delete_word(self, app: &mut App) -> Result<(), AppError> {
+        if app.is_replaying() {
+            return Ok(());
+        }
+        match app.tracker.delete_word() {
+            Ok(()) => Ok(()),
             Err(AppError::TypingTestNotInProgress) => Ok(()),
             Err(AppError::IllegalBackspace) => Ok(()),
             Err(AppError::IllegalSpaceCharacter) => Ok(()),
@@ -263,11 +298,62 @@ impl AppHandler {
         }
     }
 
+    pub fn handle_finish(self, app: &mut App) -> Result<(), AppError> {
+        if !app.tracker.mode.is_zen_mode() || app.is_replaying() {
+            return Ok(());
+        }
+        match app.tracker.finish() {
+            Ok(()) => Ok(()),
+            Err(AppError::TypingTestNotInProgress) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
     pub fn handle_set_line_count(self, app: &mut App, line_count: u8) -> Result<(), AppError> {
         app.config.change_visible_lines_count(line_count);
         Ok(())
     }
 
+    pub fn handle_set_error_policy(
+        self,
+        app: &mut App,
+        policy: ErrorPolicy,
+    ) -> Result<(), AppError> {
+        app.config.change_error_policy(policy);
+        app.restart()?;
+        Ok(())
+    }
+
+    pub fn handle_set_difficulty(
+        self,
+        app: &mut App,
+        difficulty: Difficulty,
+    ) -> Result<(), AppError> {
+        app.config.change_difficulty(difficulty);
+        app.restart()?;
+        Ok(())
+    }
+
+    pub fn handle_set_pace_caret(self, app: &mut App, pace: PaceCaret) -> Result<(), AppError> {
+        app.config.change_pace_caret(pace);
+        app.restart()?;
+        Ok(())
+    }
+
+    pub fn handle_set_practice_repetitions(
+        self,
+        app: &mut App,
+        repetitions: u8,
+    ) -> Result<(), AppError> {
+        app.config.change_practice_repetitions(repetitions);
+        Ok(())
+    }
+
+    pub fn handle_set_memory(self, app: &mut App, secs: u8) -> Result<(), AppError> {
+        app.config.change_memory(secs);
+        Ok(())
+    }
+
     pub fn handle_set_cursor(self, app: &mut App, variant: CursorVariant) -> Result<(), AppError> {
         app.config.change_cursor_variant(variant);
         // app.restart()?;
@@ -296,12 +382,42 @@ impl AppHandler {
         Ok(())
     }
 
+    pub fn handle_set_quote(self, app: &mut App, length: QuoteLength) -> Result<(), AppError> {
+        app.config.change_mode(config::Mode::Quote(length))?;
+        app.restart()?;
+        Ok(())
+    }
+
+    pub fn handle_set_zen(self, app: &mut App) -> Result<(), AppError> {
+        app.config.change_mode(config::Mode::Zen)?;
+        app.restart()?;
+        Ok(())
+    }
+
+    pub fn handle_set_code(self, app: &mut App) -> Result<(), AppError> {
+        app.config.change_mode(config::Mode::Code)?;
+        app.restart()?;
+        Ok(())
+    }
+
     pub fn handle_set_language(self, app: &mut App, lang: String) -> Result<(), AppError> {
         app.config.change_language(lang);
         app.restart()?;
         Ok(())
     }
 
+    pub fn handle_set_layout(self, app: &mut App, layout: String) -> Result<(), AppError> {
+        match KeyboardLayout::load(&layout) {
+            Ok(keyboard_layout) => {
+                app.config.change_layout(layout);
+                app.layout = keyboard_layout;
+                app.restart()?;
+            }
+            Err(err) => notify_error!(err.to_string()),
+        }
+        Ok(())
+    }
+
     pub fn handle_set_ascii_art(self, app: &mut App, art: String) -> Result<(), AppError> {
         // NOTE(ema): this feels a little bit to "side-effecty", but selecting an ascii art without
         // having the `ResultsVariant::Neofetch` as the current variant feels pointless, so yeah.
@@ -416,4 +532,72 @@ impl AppHandler {
 
         Ok(())
     }
+
+    pub fn handle_leaderboard_key_stats(self, app: &mut App) -> Result<(), AppError> {
+        let Some(ref db) = app.db else {
+            return Ok(());
+        };
+
+        if let Some(ref mut leaderboard) = app.leaderboard {
+            leaderboard.toggle_key_stats(db);
+        }
+
+        Ok(())
+    }
+
+    pub fn handle_leaderboard_toggle_invalid(self, app: &mut App) -> Result<(), AppError> {
+        let Some(ref db) = app.db else {
+            return Ok(());
+        };
+
+        if let Some(ref mut leaderboard) = app.leaderboard {
+            leaderboard.toggle_invalid(db);
+        }
+
+        Ok(())
+    }
+
+    pub fn handle_leaderboard_replay(self, app: &mut App) -> Result<(), AppError> {
+        let Some(id) = app
+            .leaderboard
+            .as_ref()
+            // the table is hidden behind the key statistics
+            .filter(|leaderboard| leaderboard.key_stats().is_none())
+            .and_then(|leaderboard| leaderboard.selected())
+            .and_then(|result| result.id)
+        else {
+            return Ok(());
+        };
+
+        app.leaderboard = None;
+        app.tracker.unpause();
+        app.start_replay(id);
+        Ok(())
+    }
+
+    pub fn handle_practice_ngrams(self, app: &mut App) -> Result<(), AppError> {
+        let ngrams = NgramStats::from_tracker(&app.tracker).weakest(NgramStats::SUMMARY_COUNT, 1);
+        app.start_ngram_practice(&ngrams)
+    }
+
+    pub fn handle_leaderboard_practice_ngrams(self, app: &mut App) -> Result<(), AppError> {
+        // only offered while the all-time statistics are shown
+        let Some(ngrams) = app
+            .leaderboard
+            .as_ref()
+            .and_then(|leaderboard| leaderboard.ngram_stats())
+            .map(|stats| {
+                stats.weakest(
+                    NgramStats::SUMMARY_COUNT,
+                    NgramStats::ALL_TIME_MIN_OCCURRENCES,
+                )
+            })
+        else {
+            return Ok(());
+        };
+
+        app.leaderboard = None;
+        app.tracker.unpause();
+        app.start_ngram_practice(&ngrams)
+    }
 }
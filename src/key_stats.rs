@@ -0,0 +1,479 @@
+use crate::tracker::Tracker;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, time::Instant};
+
+/// Typing statistics of a single key
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeyStat {
+    /// Times the key was expected to be typed
+    pub attempts: u32,
+    /// Times something else was typed instead
+    pub misses: u32,
+    /// Sum of the time since the previous keystroke, in milliseconds
+    pub latency_ms: u64,
+    /// Number of keystrokes `latency_ms` adds up
+    pub latency_samples: u32,
+    /// What was typed instead of the key and how many times
+    pub substitutions: BTreeMap<char, u32>,
+}
+
+impl KeyStat {
+    pub fn miss_rate(&self) -> f64 {
+        if self.attempts == 0 {
+            return 0.0;
+        }
+        self.misses as f64 / self.attempts as f64
+    }
+
+    pub fn average_latency_ms(&self) -> Option<f64> {
+        if self.latency_samples == 0 {
+            return None;
+        }
+        Some(self.latency_ms as f64 / self.latency_samples as f64)
+    }
+
+    /// The most common wrong key typed instead of this one
+    pub fn top_substitution(&self) -> Option<char> {
+        self.substitutions
+            .iter()
+            .max_by_key(|(_, count)| **count)
+            .map(|(c, _)| *c)
+    }
+
+    pub fn merge(&mut self, other: &KeyStat) {
+        self.attempts += other.attempts;
+        self.misses += other.misses;
+        self.latency_ms += other.latency_ms;
+        self.latency_samples += other.latency_samples;
+        for (c, count) in &other.substitutions {
+            *self.substitutions.entry(*c).or_default() += count;
+        }
+    }
+}
+
+/// Per key typing statistics of one or more tests
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeyStats {
+    keys: BTreeMap<char, KeyStat>,
+}
+
+impl KeyStats {
+    /// Aggregates the statistics of the keys typed so far. Only the final state of each token
+    /// counts, a mistake that was corrected is not a miss
+    pub fn from_tracker(tracker: &Tracker) -> Self {
+        let mut stats = Self::default();
+        // zen tests have no target to miss
+        if tracker.mode.is_zen_mode() {
+            return stats;
+        }
+
+        let mut last_typed_at = None;
+        for (pos, token) in tracker.tokens.iter().enumerate() {
+            let (Some(typed), Some(typed_at)) = (&token.typed, token.typed_at) else {
+                continue;
+            };
+            let previous = last_typed_at.replace(typed_at);
+            if token.is_extra_token() || token.is_skipped || tracker.is_auto_typed_at(pos) {
+                continue;
+            }
+
+            // graphemes made of several code points are told apart by their base character
+            let stat = stats.keys.entry(token.target.base()).or_default();
+            stat.attempts += 1;
+            if token.is_wrong {
+                stat.misses += 1;
+                *stat.substitutions.entry(typed.base()).or_default() += 1;
+            }
+            if let Some(previous) = previous {
+                stat.latency_ms += typed_at.saturating_duration_since(previous).as_millis() as u64;
+                stat.latency_samples += 1;
+            }
+        }
+        stats
+    }
+
+    pub fn get(&self, key: char) -> Option<&KeyStat> {
+        self.keys.get(&key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (char, &KeyStat)> {
+        self.keys.iter().map(|(c, stat)| (*c, stat))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Adds the statistics of a key, on top of what is already there for it
+    pub fn add(&mut self, key: char, stat: &KeyStat) {
+        self.keys.entry(key).or_default().merge(stat);
+    }
+
+    /// Keys with the highest miss rate, worst first. Keys that were never missed are left out
+    pub fn most_missed(&self, count: usize) -> Vec<(char, &KeyStat)> {
+        let mut keys: Vec<_> = self.iter().filter(|(_, stat)| stat.misses > 0).collect();
+        keys.sort_by(|(_, a), (_, b)| b.miss_rate().total_cmp(&a.miss_rate()));
+        keys.truncate(count);
+        keys
+    }
+
+    /// Keys with the highest average latency, slowest first
+    pub fn slowest(&self, count: usize) -> Vec<(char, &KeyStat)> {
+        let mut keys: Vec<_> = self
+            .iter()
+            .filter(|(_, stat)| stat.average_latency_ms().is_some())
+            .collect();
+        keys.sort_by(|(_, a), (_, b)| {
+            let (a, b) = (a.average_latency_ms(), b.average_latency_ms());
+            b.unwrap_or(0.0).total_cmp(&a.unwrap_or(0.0))
+        });
+        keys.truncate(count);
+        keys
+    }
+
+    /// How poorly every key is typed: ten times its miss rate plus how much slower than the
+    /// average key it is. A key missed 10% of the time and typed 50% slower than average weighs
+    /// 1.5, keys typed cleanly at or above the average speed weigh 0. Keys attempted fewer than
+    /// `min_attempts` times are left out
+    pub fn weaknesses(&self, min_attempts: u32) -> BTreeMap<char, f64> {
+        const MISS_RATE_WEIGHT: f64 = 10.0;
+
+        let (latency_ms, samples) = self.keys.values().fold((0, 0), |(ms, samples), stat| {
+            (ms + stat.latency_ms, samples + stat.latency_samples)
+        });
+        let average_latency_ms = (samples > 0).then(|| latency_ms as f64 / samples as f64);
+
+        self.iter()
+            .filter(|(_, stat)| stat.attempts >= min_attempts)
+            .map(|(c, stat)| {
+                let slowness = match (stat.average_latency_ms(), average_latency_ms) {
+                    (Some(latency), Some(average)) if average > 0.0 => {
+                        (latency / average - 1.0).max(0.0)
+                    }
+                    _ => 0.0,
+                };
+                (c, stat.miss_rate() * MISS_RATE_WEIGHT + slowness)
+            })
+            .filter(|(_, weakness)| *weakness > 0.0)
+            .collect()
+    }
+}
+
+/// Typing statistics of a sequence of adjacent keys, i.e `th` or `ing`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NgramStat {
+    /// Times the sequence was typed
+    pub occurrences: u32,
+    /// Times any key of the sequence was missed
+    pub misses: u32,
+    /// Sum of the time from the first to the last key of the sequence, in milliseconds
+    pub latency_ms: u64,
+}
+
+impl NgramStat {
+    pub fn miss_rate(&self) -> f64 {
+        if self.occurrences == 0 {
+            return 0.0;
+        }
+        self.misses as f64 / self.occurrences as f64
+    }
+
+    pub fn average_latency_ms(&self) -> f64 {
+        if self.occurrences == 0 {
+            return 0.0;
+        }
+        self.latency_ms as f64 / self.occurrences as f64
+    }
+
+    pub fn merge(&mut self, other: &NgramStat) {
+        self.occurrences += other.occurrences;
+        self.misses += other.misses;
+        self.latency_ms += other.latency_ms;
+    }
+}
+
+/// Bigram and trigram statistics of one or more tests. Sequences never cross word boundaries
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NgramStats {
+    ngrams: BTreeMap<String, NgramStat>,
+}
+
+impl NgramStats {
+    pub const MIN_LEN: usize = 2;
+    pub const MAX_LEN: usize = 3;
+    /// How many sequences are shown and practiced at once
+    pub const SUMMARY_COUNT: usize = 5;
+    /// Sequences typed fewer times than this across all tests are not ranked
+    pub const ALL_TIME_MIN_OCCURRENCES: u32 = 3;
+
+    pub fn from_tracker(tracker: &Tracker) -> Self {
+        let mut stats = Self::default();
+        if tracker.mode.is_zen_mode() {
+            return stats;
+        }
+
+        // keys typed by the user in the current word, in order
+        let mut run: Vec<(char, Instant, bool)> = Vec::new();
+        for (pos, token) in tracker.tokens.iter().enumerate() {
+            let typed_at = match token.typed_at {
+                Some(typed_at)
+                    if !token.is_separator()
+                        && !token.is_extra_token()
+                        && !token.is_skipped
+                        && !tracker.is_auto_typed_at(pos) =>
+                {
+                    typed_at
+                }
+                _ => {
+                    run.clear();
+                    continue;
+                }
+            };
+
+            let base = token.target.base();
+            run.push((
+                base.to_lowercase().next().unwrap_or(base),
+                typed_at,
+                token.is_wrong,
+            ));
+            for len in Self::MIN_LEN..=Self::MAX_LEN.min(run.len()) {
+                let window = &run[run.len() - len..];
+                let ngram: String = window.iter().map(|(c, _, _)| *c).collect();
+                let stat = stats.ngrams.entry(ngram).or_default();
+                stat.occurrences += 1;
+                if window.iter().any(|(_, _, is_wrong)| *is_wrong) {
+                    stat.misses += 1;
+                }
+                stat.latency_ms +=
+                    typed_at.saturating_duration_since(window[0].1).as_millis() as u64;
+            }
+        }
+        stats
+    }
+
+    pub fn get(&self, ngram: &str) -> Option<&NgramStat> {
+        self.ngrams.get(ngram)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &NgramStat)> {
+        self.ngrams
+            .iter()
+            .map(|(ngram, stat)| (ngram.as_str(), stat))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ngrams.is_empty()
+    }
+
+    /// Adds the statistics of a sequence, on top of what is already there for it
+    pub fn add(&mut self, ngram: &str, stat: &NgramStat) {
+        self.ngrams
+            .entry(ngram.to_string())
+            .or_default()
+            .merge(stat);
+    }
+
+    /// Sequences with the highest average latency per key transition, slowest first. Sequences
+    /// typed fewer than `min_occurrences` times are too noisy to be ranked
+    pub fn slowest(&self, count: usize, min_occurrences: u32) -> Vec<(&str, &NgramStat)> {
+        let per_transition = |(ngram, stat): &(&str, &NgramStat)| {
+            stat.average_latency_ms() / (ngram.chars().count() - 1) as f64
+        };
+        let mut ngrams: Vec<_> = self
+            .iter()
+            .filter(|(_, stat)| stat.occurrences >= min_occurrences)
+            .collect();
+        ngrams.sort_by(|a, b| per_transition(b).total_cmp(&per_transition(a)));
+        ngrams.truncate(count);
+        ngrams
+    }
+
+    /// Sequences with the highest miss rate, worst first. Sequences that were never missed are
+    /// left out
+    pub fn most_missed(&self, count: usize, min_occurrences: u32) -> Vec<(&str, &NgramStat)> {
+        let mut ngrams: Vec<_> = self
+            .iter()
+            .filter(|(_, stat)| stat.occurrences >= min_occurrences && stat.misses > 0)
+            .collect();
+        ngrams.sort_by(|(_, a), (_, b)| b.miss_rate().total_cmp(&a.miss_rate()));
+        ngrams.truncate(count);
+        ngrams
+    }
+
+    /// The sequences worth practicing: the slowest and the most missed ones, without repeats
+    pub fn weakest(&self, count: usize, min_occurrences: u32) -> Vec<String> {
+        let mut weakest: Vec<String> = Vec::with_capacity(count * 2);
+        let slowest = self.slowest(count, min_occurrences);
+        let most_missed = self.most_missed(count, min_occurrences);
+        for (ngram, _) in most_missed.into_iter().chain(slowest) {
+            if !weakest.iter().any(|w| w == ngram) {
+                weakest.push(ngram.to_string());
+            }
+        }
+        weakest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Mode;
+    use std::time::Duration;
+
+    #[test]
+    fn test_key_stats_from_tracker() {
+        let mut tracker = Tracker::new("abab cd".to_string(), Mode::with_words(2));
+        for c in "xbab".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        tracker.type_char(' ').unwrap();
+        tracker.type_char('v').unwrap();
+
+        let stats = KeyStats::from_tracker(&tracker);
+        let a = stats.get('a').unwrap();
+        assert_eq!(a.attempts, 2);
+        assert_eq!(a.misses, 1);
+        assert_eq!(a.miss_rate(), 0.5);
+        assert_eq!(a.top_substitution(), Some('x'));
+        assert_eq!(stats.get('b').unwrap().misses, 0);
+        assert_eq!(stats.get('c').unwrap().top_substitution(), Some('v'));
+        // not typed yet
+        assert!(stats.get('d').is_none());
+
+        let most_missed: Vec<char> = stats.most_missed(5).iter().map(|(c, _)| *c).collect();
+        assert_eq!(most_missed, vec!['c', 'a']);
+    }
+
+    #[test]
+    fn test_key_latency() {
+        let mut tracker = Tracker::new("ab".to_string(), Mode::with_words(1));
+        tracker.type_char('a').unwrap();
+        tracker.type_char('b').unwrap();
+        let start = Instant::now();
+        tracker.tokens[0].typed_at = Some(start);
+        tracker.tokens[1].typed_at = Some(start + Duration::from_millis(150));
+
+        let stats = KeyStats::from_tracker(&tracker);
+        // the first keystroke has nothing to be measured against
+        assert_eq!(stats.get('a').unwrap().average_latency_ms(), None);
+        assert_eq!(stats.get('b').unwrap().average_latency_ms(), Some(150.0));
+        assert_eq!(stats.slowest(1)[0].0, 'b');
+    }
+
+    #[test]
+    fn test_merge_key_stats() {
+        let mut stats = KeyStats::default();
+        let stat = KeyStat {
+            attempts: 4,
+            misses: 1,
+            latency_ms: 400,
+            latency_samples: 4,
+            substitutions: BTreeMap::from([('s', 1)]),
+        };
+        stats.add('a', &stat);
+        stats.add('a', &stat);
+
+        let a = stats.get('a').unwrap();
+        assert_eq!(a.attempts, 8);
+        assert_eq!(a.misses, 2);
+        assert_eq!(a.average_latency_ms(), Some(100.0));
+        assert_eq!(a.substitutions.get(&'s'), Some(&2));
+    }
+
+    #[test]
+    fn test_weaknesses() {
+        let mut stats = KeyStats::default();
+        let stat = |attempts, misses, latency_ms| KeyStat {
+            attempts,
+            misses,
+            latency_ms,
+            latency_samples: attempts,
+            ..Default::default()
+        };
+        stats.add('a', &stat(10, 0, 1000));
+        stats.add('b', &stat(10, 1, 1000));
+        stats.add('c', &stat(10, 0, 4000));
+        stats.add('d', &stat(2, 2, 400));
+
+        let weaknesses = stats.weaknesses(5);
+        // typed cleanly and faster than average
+        assert!(!weaknesses.contains_key(&'a'));
+        assert!((weaknesses[&'b'] - 1.0).abs() < f64::EPSILON);
+        assert!((weaknesses[&'c'] - 1.0).abs() < f64::EPSILON);
+        // not enough attempts to tell
+        assert!(!weaknesses.contains_key(&'d'));
+    }
+
+    #[test]
+    fn test_ngram_stats_from_tracker() {
+        let mut tracker = Tracker::new("the then".to_string(), Mode::with_words(2));
+        for c in "thx then".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        let start = Instant::now();
+        for (idx, token) in tracker.tokens.iter_mut().enumerate() {
+            token.typed_at = Some(start + Duration::from_millis(idx as u64 * 100));
+        }
+
+        let stats = NgramStats::from_tracker(&tracker);
+        let th = stats.get("th").unwrap();
+        assert_eq!(th.occurrences, 2);
+        assert_eq!(th.misses, 0);
+        assert_eq!(th.average_latency_ms(), 100.0);
+        assert_eq!(stats.get("he").unwrap().misses, 1);
+        assert_eq!(stats.get("the").unwrap().latency_ms, 400);
+        assert_eq!(stats.get("then").map(|s| s.occurrences), None);
+        // sequences don't cross the space
+        assert!(stats.get("et").is_none());
+        assert!(stats.get("e ").is_none());
+
+        let most_missed: Vec<&str> = stats.most_missed(5, 1).iter().map(|(n, _)| *n).collect();
+        assert_eq!(most_missed, vec!["he", "the"]);
+        // the most missed sequence comes first, then the slowest one
+        let weakest = stats.weakest(1, 1);
+        assert_eq!(weakest.len(), 2);
+        assert_eq!(weakest[0], "he");
+    }
+
+    #[test]
+    fn test_ngram_min_occurrences() {
+        let mut stats = NgramStats::default();
+        let slow = NgramStat {
+            occurrences: 1,
+            misses: 1,
+            latency_ms: 900,
+        };
+        let fast = NgramStat {
+            occurrences: 5,
+            misses: 1,
+            latency_ms: 500,
+        };
+        stats.add("qz", &slow);
+        stats.add("in", &fast);
+
+        assert_eq!(stats.slowest(5, 1)[0].0, "qz");
+        assert_eq!(stats.slowest(5, 3)[0].0, "in");
+        assert_eq!(stats.most_missed(5, 3).len(), 1);
+    }
+
+    #[test]
+    fn test_non_ascii_ngrams() {
+        let mut tracker = Tracker::new("Ñandú ñandú".to_string(), Mode::with_words(2));
+        for c in "Ñandú ñandú".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        let stats = NgramStats::from_tracker(&tracker);
+        assert_eq!(stats.get("ña").unwrap().occurrences, 2);
+
+        // latency is per key transition, not per byte
+        let mut stats = NgramStats::default();
+        let stat = |latency_ms| NgramStat {
+            occurrences: 1,
+            misses: 0,
+            latency_ms,
+        };
+        stats.add("dú", &stat(300));
+        stats.add("nd", &stat(200));
+        assert_eq!(stats.slowest(1, 1)[0].0, "dú");
+    }
+}
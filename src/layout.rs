@@ -0,0 +1,157 @@
+use crate::{
+    assets,
+    common::filesystem::config_dir,
+    constants::DEFAULT_LAYOUT,
+    error::{AppError, AppResult},
+};
+use serde::Deserialize;
+use std::{collections::HashMap, fs};
+
+/// Directory inside the config dir where user defined layouts live
+const USER_LAYOUTS_DIR: &str = "layouts";
+
+/// Emulates a keyboard layout on top of a QWERTY keyboard, so a new layout can be learned without
+/// changing the one of the OS. Typed characters are translated before they reach the test.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyboardLayout {
+    name: String,
+    keys: HashMap<char, char>,
+}
+
+/// A layout file, bundled in `assets/layouts` or dropped in `<config dir>/layouts`. Each row of
+/// `layout` lists what the keys of the same row of `qwerty` type in the emulated layout
+#[derive(Debug, Deserialize)]
+struct LayoutFile {
+    name: String,
+    qwerty: Vec<String>,
+    layout: Vec<String>,
+}
+
+impl Default for KeyboardLayout {
+    fn default() -> Self {
+        Self::qwerty()
+    }
+}
+
+impl KeyboardLayout {
+    /// The layout of the keyboard itself, nothing gets translated
+    pub fn qwerty() -> Self {
+        Self {
+            name: DEFAULT_LAYOUT.to_string(),
+            keys: HashMap::new(),
+        }
+    }
+
+    /// Loads a layout by name. User layouts take precedence over the bundled ones
+    pub fn load(name: &str) -> AppResult<Self> {
+        if name == DEFAULT_LAYOUT {
+            return Ok(Self::qwerty());
+        }
+        let json = user_layout_path(name)
+            .and_then(|path| fs::read_to_string(path).ok())
+            .or_else(|| assets::get_layout(name))
+            .ok_or_else(|| AppError::InvalidLayout(format!("'{name}' not found")))?;
+        Self::parse(&json)
+    }
+
+    fn parse(json: &str) -> AppResult<Self> {
+        let file: LayoutFile = serde_json::from_str(json)?;
+        if file.qwerty.len() != file.layout.len() {
+            return Err(AppError::InvalidLayout(format!(
+                "'{}' has {} qwerty rows but {} layout rows",
+                file.name,
+                file.qwerty.len(),
+                file.layout.len()
+            )));
+        }
+
+        let mut keys = HashMap::new();
+        for (qwerty, layout) in file.qwerty.iter().zip(&file.layout) {
+            if qwerty.chars().count() != layout.chars().count() {
+                return Err(AppError::InvalidLayout(format!(
+                    "'{}' rows '{qwerty}' and '{layout}' differ in length",
+                    file.name
+                )));
+            }
+            keys.extend(qwerty.chars().zip(layout.chars()).filter(|(q, l)| q != l));
+        }
+
+        Ok(Self {
+            name: file.name,
+            keys,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// What the key that types `c` on QWERTY types on this layout
+    pub fn translate(&self, c: char) -> char {
+        self.keys.get(&c).copied().unwrap_or(c)
+    }
+}
+
+fn user_layout_path(name: &str) -> Option<std::path::PathBuf> {
+    config_dir()
+        .ok()
+        .map(|dir| dir.join(USER_LAYOUTS_DIR).join(format!("{name}.json")))
+}
+
+/// Every layout that can be selected: QWERTY, the bundled ones and the user ones
+pub fn list_layouts() -> Vec<String> {
+    let mut layouts = vec![DEFAULT_LAYOUT.to_string()];
+    layouts.extend(assets::list_layouts());
+
+    if let Ok(dir) = config_dir()
+        && let Ok(entries) = fs::read_dir(dir.join(USER_LAYOUTS_DIR))
+    {
+        layouts.extend(entries.filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            path.file_stem()?.to_str().map(String::from)
+        }));
+    }
+
+    layouts[1..].sort();
+    layouts.dedup();
+    layouts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_layouts() {
+        let layouts = list_layouts();
+        assert_eq!(layouts[0], DEFAULT_LAYOUT);
+        for name in &layouts {
+            let layout = KeyboardLayout::load(name).unwrap();
+            assert_eq!(layout.name(), name);
+        }
+
+        let dvorak = KeyboardLayout::load("dvorak").unwrap();
+        let typed: String = "jdpp".chars().map(|c| dvorak.translate(c)).collect();
+        assert_eq!(typed, "hell");
+        assert_eq!(dvorak.translate('Q'), '"');
+        assert_eq!(dvorak.translate(' '), ' ');
+
+        let colemak = KeyboardLayout::load("colemak").unwrap();
+        let typed: String = "hkuu;".chars().map(|c| colemak.translate(c)).collect();
+        assert_eq!(typed, "hello");
+
+        assert_eq!(KeyboardLayout::qwerty().translate('q'), 'q');
+        assert!(KeyboardLayout::load("azerty-ish").is_err());
+    }
+
+    #[test]
+    fn test_invalid_layout_file() {
+        let json = r#"{"name":"broken","qwerty":["abc"],"layout":["ab"]}"#;
+        assert!(KeyboardLayout::parse(json).is_err());
+        let json = r#"{"name":"broken","qwerty":["abc"],"layout":[]}"#;
+        assert!(KeyboardLayout::parse(json).is_err());
+    }
+}
@@ -1,5 +1,6 @@
-use crate::db::{
-    Db, LeaderboardColumn, LeaderboardQuery, LeaderboardResult, LeaderboardState, SortOrder,
+use crate::{
+    db::{Db, LeaderboardColumn, LeaderboardQuery, LeaderboardResult, LeaderboardState, SortOrder},
+    key_stats::{KeyStats, NgramStats},
 };
 use ratatui::widgets::TableState;
 
@@ -93,6 +94,10 @@ pub struct Leaderboard {
     query: LeaderboardQuery,
     pub state: Option<LeaderboardState>,
     pub table: TableState,
+    /// All-time per key statistics, shown instead of the results table while set
+    key_stats: Option<KeyStats>,
+    /// All-time bigram and trigram statistics, loaded along with `key_stats`
+    ngram_stats: Option<NgramStats>,
 }
 
 impl Leaderboard {
@@ -133,6 +138,8 @@ impl Leaderboard {
         self.state = None;
         self.query.offset = 0;
         self.table = TableState::default();
+        self.key_stats = None;
+        self.ngram_stats = None;
     }
 
     pub fn data(&self) -> &[LeaderboardResult] {
@@ -143,6 +150,11 @@ impl Leaderboard {
         }
     }
 
+    /// The result under the table cursor
+    pub fn selected(&self) -> Option<&LeaderboardResult> {
+        self.table.selected().and_then(|idx| self.data().get(idx))
+    }
+
     pub fn is_empty(&self) -> bool {
         self.state.as_ref().is_some_and(|s| s.data.is_empty())
     }
@@ -247,6 +259,43 @@ impl Leaderboard {
         self.table.select(Some(0));
     }
 
+    /// Switches between the results table and the all-time key statistics
+    pub fn toggle_key_stats(&mut self, db: &Db) {
+        if self.key_stats.take().is_some() {
+            self.ngram_stats = None;
+            return;
+        }
+        match db.all_time_key_stats().and_then(|stats| {
+            let ngrams = db.all_time_ngram_stats()?;
+            Ok((stats, ngrams))
+        }) {
+            Ok((stats, ngrams)) => {
+                self.key_stats = Some(stats);
+                self.ngram_stats = Some(ngrams);
+            }
+            Err(e) => self.status = LeaderboardStatus::Error(e.to_string()),
+        }
+    }
+
+    /// Switches between ranking only valid results and listing every result
+    pub fn toggle_invalid(&mut self, db: &Db) {
+        self.query.include_invalid = !self.query.include_invalid;
+        self.load(db, LoadType::Refresh);
+        self.table.select(Some(0));
+    }
+
+    pub fn shows_invalid(&self) -> bool {
+        self.query.include_invalid
+    }
+
+    pub fn key_stats(&self) -> Option<&KeyStats> {
+        self.key_stats.as_ref()
+    }
+
+    pub fn ngram_stats(&self) -> Option<&NgramStats> {
+        self.ngram_stats.as_ref()
+    }
+
     pub fn current_sort(&self) -> (&LeaderboardColumn, &SortOrder) {
         (&self.query.sort_by, &self.query.sort_order)
     }
@@ -326,6 +375,25 @@ mod tests {
         assert!(leaderboard.is_open());
     }
 
+    #[test]
+    fn test_toggle_key_stats() {
+        let db = create_test_db();
+        let mut leaderboard = Leaderboard::new();
+        leaderboard.open(&db);
+        assert!(leaderboard.key_stats().is_none());
+
+        leaderboard.toggle_key_stats(&db);
+        assert!(leaderboard.key_stats().is_some());
+        assert!(leaderboard.ngram_stats().is_some());
+        leaderboard.toggle_key_stats(&db);
+        assert!(leaderboard.key_stats().is_none());
+        assert!(leaderboard.ngram_stats().is_none());
+
+        leaderboard.toggle_key_stats(&db);
+        leaderboard.close();
+        assert!(leaderboard.key_stats().is_none());
+    }
+
     #[test]
     fn test_offset_calculation() {
         let mut db = create_test_db();
@@ -1,4 +1,7 @@
-use crate::{config::Config, db::reset_database, persistence::reset_persistence};
+use crate::{
+    common::filesystem::read_document, config::Config, db::reset_database,
+    persistence::reset_persistence,
+};
 use clap::Parser;
 use crossterm::{
     cursor::SetCursorStyle,
@@ -21,8 +24,11 @@ pub mod constants;
 pub mod db;
 pub mod db_writer;
 pub mod error;
+pub mod events;
 pub mod handler;
 pub mod input;
+pub mod key_stats;
+pub mod layout;
 pub mod leaderboard;
 pub mod logger;
 pub mod menu;
@@ -30,6 +36,7 @@ pub mod modal;
 pub mod notifications;
 pub mod perf;
 pub mod persistence;
+pub mod replay;
 pub mod theme;
 pub mod tracker;
 pub mod tui;
@@ -43,9 +50,14 @@ pub mod prelude {
 
 pub fn start() -> anyhow::Result<()> {
     logger::init()?;
-    let args = cli::Cli::parse();
+    let mut args = cli::Cli::parse();
     args.validate().map_err(|e| anyhow::anyhow!(e))?;
 
+    // documents are typed as custom words, they just happen to keep their line breaks
+    if let Some(path) = &args.file {
+        args.words = Some(read_document(path)?);
+    }
+
     if args.reset {
         reset_persistence()?;
         reset_database()?;
@@ -14,10 +14,18 @@ pub enum MenuContext {
     Themes,
     Time,
     Words,
+    Quote,
     Language,
+    Layout,
+    Funbox,
     Cursor,
     Ascii,
     VisibleLines,
+    ErrorPolicy,
+    Difficulty,
+    PaceCaret,
+    PracticeRepetitions,
+    Memory,
     Leaderboard,
     About,
     CommandPalette,
@@ -1,6 +1,6 @@
 use crate::constants::{
-    MAX_CUSTOM_LINE_COUNT, MAX_CUSTOM_TIME, MAX_CUSTOM_WORD_COUNT, MIN_CUSTOM_TIME,
-    MIN_CUSTOM_WORD_COUNT,
+    MAX_CUSTOM_LINE_COUNT, MAX_CUSTOM_TIME, MAX_CUSTOM_WORD_COUNT, MAX_MIN_WPM, MAX_PACE_CARET_WPM,
+    MIN_CUSTOM_TIME, MIN_CUSTOM_WORD_COUNT,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -23,6 +23,9 @@ pub enum ModalContext {
     CustomTime,
     CustomWordCount,
     CustomLineCount,
+    MinAccuracy,
+    MinWpm,
+    PaceCaretWpm,
     ExitConfirmation,
 }
 
@@ -81,6 +84,48 @@ impl Modal {
                     max_val: MAX_CUSTOM_LINE_COUNT as u16,
                 }),
             },
+            ModalContext::MinAccuracy => Modal {
+                ctx,
+                kind: ModalKind::Input,
+                title: "Minimum Accuracy".to_string(),
+                description: "Fail below this accuracy (0 disables it)".to_string(),
+                buffer: Some(InputBuffer {
+                    input: String::new(),
+                    cursor_pos: 0,
+                    is_numeric: true,
+                    error: None,
+                    min_val: 0,
+                    max_val: 100,
+                }),
+            },
+            ModalContext::MinWpm => Modal {
+                ctx,
+                kind: ModalKind::Input,
+                title: "Minimum WPM".to_string(),
+                description: "Fail below this WPM (0 disables it)".to_string(),
+                buffer: Some(InputBuffer {
+                    input: String::new(),
+                    cursor_pos: 0,
+                    is_numeric: true,
+                    error: None,
+                    min_val: 0,
+                    max_val: MAX_MIN_WPM,
+                }),
+            },
+            ModalContext::PaceCaretWpm => Modal {
+                ctx,
+                kind: ModalKind::Input,
+                title: "Pace Caret".to_string(),
+                description: "Enter the pace caret speed (WPM)".to_string(),
+                buffer: Some(InputBuffer {
+                    input: String::new(),
+                    cursor_pos: 0,
+                    is_numeric: true,
+                    error: None,
+                    min_val: 1,
+                    max_val: MAX_PACE_CARET_WPM,
+                }),
+            },
             ModalContext::ExitConfirmation => Modal {
                 ctx,
                 kind: ModalKind::Confirmation,
@@ -261,11 +306,12 @@ mod tests {
     fn test_modal_above_max_value() {
         let mut modal = Modal::new(ModalContext::CustomTime);
         modal.handle_input('3');
+        modal.handle_input('6');
         modal.handle_input('0');
         modal.handle_input('1');
         assert_eq!(
             modal.buffer.as_ref().unwrap().error,
-            Some("Value must not exceed 300".to_string())
+            Some("Value must not exceed 3600".to_string())
         );
         assert_eq!(modal.get_value(), Err("Invalid Input"));
     }
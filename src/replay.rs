@@ -0,0 +1,284 @@
+use crate::{
+    common::grapheme::Folding,
+    config::Mode,
+    tracker::{Keystroke, KeystrokeKind, Tracker, TrackerOptions},
+};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Time the replayed text is shown before the first keystroke is played back
+const REPLAY_LEAD_IN: Duration = Duration::from_secs(1);
+
+/// Everything needed to play a finished typing test back
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    /// The target text of the test. Empty for zen tests, their text is whatever was typed
+    pub text: String,
+    pub mode: Mode,
+    pub options: TrackerOptions,
+    pub keystrokes: Vec<Keystroke>,
+    /// Folding table the test ran with, lazy tests depend on it
+    #[serde(default)]
+    pub folding: Folding,
+}
+
+impl Recording {
+    pub fn from_tracker(tracker: &Tracker) -> Self {
+        let text = if tracker.mode.is_zen_mode() {
+            String::new()
+        } else {
+            tracker.text.clone()
+        };
+        Self {
+            text,
+            mode: tracker.mode,
+            options: tracker.options,
+            keystrokes: tracker.keystrokes.clone(),
+            folding: tracker.folding.clone(),
+        }
+    }
+}
+
+/// Plays a recording back through a `Tracker` at its original speed
+#[derive(Debug, Clone)]
+pub struct Replay {
+    keystrokes: Vec<Keystroke>,
+    next: usize,
+    started_at: Instant,
+}
+
+impl Replay {
+    /// Resets the tracker to the recorded test and starts playing it back
+    pub fn start(recording: Recording, tracker: &mut Tracker) -> Self {
+        tracker.options = recording.options;
+        tracker.folding = recording.folding;
+        tracker.reset(recording.text, recording.mode);
+        Self {
+            keystrokes: recording.keystrokes,
+            next: 0,
+            started_at: Instant::now(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.keystrokes.len()
+    }
+
+    /// Feeds every keystroke that is due by now. Returns whether any keystroke was fed
+    pub fn advance(&mut self, tracker: &mut Tracker) -> bool {
+        // once started, the tracker clock is the recording clock, pauses included
+        let now_ms = if tracker.start_time.is_some() {
+            let first_at = self.keystrokes.first().map_or(0, |k| k.at_ms);
+            first_at + tracker.elapsed_time().as_millis() as u64
+        } else {
+            match self.started_at.elapsed().checked_sub(REPLAY_LEAD_IN) {
+                Some(elapsed) => elapsed.as_millis() as u64,
+                None => return false,
+            }
+        };
+        self.advance_to(tracker, now_ms)
+    }
+
+    fn advance_to(&mut self, tracker: &mut Tracker, now_ms: u64) -> bool {
+        if tracker.is_paused() {
+            return false;
+        }
+
+        let mut fed = false;
+        while let Some(keystroke) = self.keystrokes.get(self.next)
+            && keystroke.at_ms <= now_ms
+        {
+            // the recording only holds keystrokes the test accepted, replaying them can't fail
+            let _ = match keystroke.kind {
+                KeystrokeKind::Char(c) => tracker.type_char(c),
+                KeystrokeKind::Backspace => tracker.backspace(),
+                KeystrokeKind::DeleteWord => tracker.delete_word(),
+            };
+            self.next += 1;
+            fed = true;
+        }
+
+        // zen tests were ended by hand
+        if fed && self.is_finished() && tracker.mode.is_zen_mode() {
+            let _ = tracker.finish();
+        }
+
+        fed
+    }
+}
+
+/// A previous run raced against during a test. Its keystrokes are played back on a shadow tracker
+/// that follows the clock of the live test
+#[derive(Debug, Clone)]
+pub struct Ghost {
+    /// WPM of the run being raced
+    pub wpm: u16,
+    recording: Recording,
+    tracker: Tracker,
+    replay: Replay,
+}
+
+impl Ghost {
+    pub fn new(recording: Recording, wpm: u16) -> Self {
+        let mut tracker = Tracker::new(String::new(), recording.mode);
+        let replay = Replay::start(recording.clone(), &mut tracker);
+        Self {
+            wpm,
+            recording,
+            tracker,
+            replay,
+        }
+    }
+
+    /// The text of the raced run, the live test has to type the same one
+    pub fn text(&self) -> &str {
+        &self.recording.text
+    }
+
+    /// Sends the ghost back to the start line
+    pub fn reset(&mut self) {
+        self.replay = Replay::start(self.recording.clone(), &mut self.tracker);
+    }
+
+    /// Moves the ghost to where the raced run was after `elapsed`. Returns whether it moved
+    pub fn advance(&mut self, elapsed: Duration) -> bool {
+        let first_at = self.replay.keystrokes.first().map_or(0, |k| k.at_ms);
+        let moved = self
+            .replay
+            .advance_to(&mut self.tracker, first_at + elapsed.as_millis() as u64);
+        // nobody observes the ghost, don't let its events pile up
+        self.tracker.take_events();
+        moved
+    }
+
+    /// Target text position of the ghost, see `Tracker::text_position`
+    pub fn position(&self) -> usize {
+        self.tracker.text_position()
+    }
+
+    /// How many characters the live test is ahead of the ghost. Negative when behind
+    pub fn chars_ahead(&self, tracker: &Tracker) -> i64 {
+        tracker.text_position() as i64 - self.position() as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_str(tracker: &mut Tracker, text: &str) {
+        for c in text.chars() {
+            if c == '<' {
+                tracker.backspace().unwrap();
+            } else if c == '^' {
+                tracker.delete_word().unwrap();
+            } else {
+                tracker.type_char(c).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_replay_reproduces_test() {
+        let options = TrackerOptions {
+            auto_close_brackets: true,
+            ..Default::default()
+        };
+        let mut original = Tracker::new("hello there world".to_string(), Mode::with_words(3))
+            .with_options(options);
+        // a fixed typo, an extra token, a space jump and a deleted word
+        type_str(&mut original, "hx<ellow< t wxx^world");
+        assert!(original.check_completion());
+
+        let recording = Recording::from_tracker(&original);
+        let mut replayed = Tracker::new(String::new(), Mode::default());
+        let mut replay = Replay::start(recording, &mut replayed);
+        assert_eq!(replayed.options, options);
+
+        assert!(replay.advance_to(&mut replayed, u64::MAX));
+        assert!(replay.is_finished());
+        assert!(replayed.check_completion());
+        assert_eq!(replayed.typed_text, original.typed_text);
+        assert_eq!(replayed.total_errors, original.total_errors);
+        assert_eq!(replayed.keystrokes.len(), original.keystrokes.len());
+    }
+
+    #[test]
+    fn test_replay_waits_for_keystroke_time() {
+        let recording = Recording {
+            text: "ab".to_string(),
+            mode: Mode::with_words(1),
+            options: TrackerOptions::default(),
+            folding: Folding::default(),
+            keystrokes: vec![
+                Keystroke {
+                    at_ms: 0,
+                    kind: KeystrokeKind::Char('a'),
+                },
+                Keystroke {
+                    at_ms: 500,
+                    kind: KeystrokeKind::Char('b'),
+                },
+            ],
+        };
+        let mut tracker = Tracker::new(String::new(), Mode::default());
+        let mut replay = Replay::start(recording, &mut tracker);
+
+        assert!(replay.advance_to(&mut tracker, 100));
+        assert_eq!(tracker.typed_text, "a");
+        assert!(!replay.advance_to(&mut tracker, 200));
+        assert!(replay.advance_to(&mut tracker, 500));
+        assert_eq!(tracker.typed_text, "ab");
+    }
+
+    #[test]
+    fn test_ghost_follows_live_clock() {
+        let mut best = Tracker::new("ab cd".to_string(), Mode::with_words(2));
+        type_str(&mut best, "ab cd");
+        let mut recording = Recording::from_tracker(&best);
+        for (idx, keystroke) in recording.keystrokes.iter_mut().enumerate() {
+            keystroke.at_ms = idx as u64 * 100;
+        }
+
+        let mut ghost = Ghost::new(recording, 120);
+        assert_eq!(ghost.text(), "ab cd");
+        assert_eq!(ghost.position(), 0);
+
+        assert!(ghost.advance(Duration::from_millis(250)));
+        assert_eq!(ghost.position(), 3);
+
+        let mut live = Tracker::new("ab cd".to_string(), Mode::with_words(2));
+        type_str(&mut live, "abx");
+        // the extra token doesn't put the live test ahead
+        assert_eq!(ghost.chars_ahead(&live), -1);
+        type_str(&mut live, "< c");
+        assert_eq!(ghost.chars_ahead(&live), 1);
+
+        ghost.reset();
+        assert_eq!(ghost.position(), 0);
+    }
+
+    #[test]
+    fn test_zen_replay_finishes() {
+        let mut original = Tracker::new(String::new(), Mode::Zen);
+        type_str(&mut original, "free text");
+        original.finish().unwrap();
+
+        let recording = Recording::from_tracker(&original);
+        assert!(recording.text.is_empty());
+
+        let mut replayed = Tracker::new(String::new(), Mode::default());
+        let mut replay = Replay::start(recording, &mut replayed);
+        replay.advance_to(&mut replayed, u64::MAX);
+        assert!(replayed.is_complete());
+        assert_eq!(replayed.text, "free text");
+    }
+
+    #[test]
+    fn test_older_recordings_still_load() {
+        // recordings saved before an option existed don't have it
+        let json = r#"{"text":"ab","mode":{"Words":1},"options":{"auto_close_brackets":false},"keystrokes":[]}"#;
+        let recording: Recording = serde_json::from_str(json).unwrap();
+        assert_eq!(recording.options, TrackerOptions::default());
+    }
+}
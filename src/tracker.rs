@@ -1,10 +1,35 @@
 use crate::{
-    config::Mode, constants::MAX_EXTRA_WRONG_CHARS, error::AppError, log_debug, notifications,
+    common::grapheme::{self, Folding, Grapheme},
+    config::{Difficulty, ErrorPolicy, Mode},
+    constants::MAX_EXTRA_WRONG_CHARS,
+    error::AppError,
+    events::TrackerEvent,
+    log_debug, notifications,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    time::{Duration, Instant},
 };
-use std::time::{Duration, Instant};
 
 const WORD_BOUNDARY_ESTIMATE_RATIO: usize = 5;
 const DEFAULT_WORD_BOUNDARY_CAPACITY: usize = 16;
+/// Live metrics are too noisy right after starting to fail a test on them
+const FAILURE_THRESHOLD_GRACE_SECS: u64 = 3;
+/// Shortest test worth ranking
+const MIN_VALID_SECONDS: usize = 15;
+const MIN_VALID_WORDS: usize = 10;
+/// A pause between two keystrokes longer than this means the typist walked away
+const IDLE_GAP_MS: u64 = 7_000;
+/// Raw WPM beyond what anyone has sustained on a keyboard
+const MAX_PLAUSIBLE_WPM: f64 = 350.0;
+/// Fewest keystroke intervals needed before judging how uniform they are
+const MIN_UNIFORM_SAMPLES: usize = 20;
+/// Human inter-key timing varies a lot more than this coefficient of variation
+const MIN_TIMING_VARIATION: f64 = 0.05;
+/// Keystrokes this close together are a terminal flushing pasted text, not key presses
+const PASTE_INTERVAL_MS: u64 = 2;
+const PASTE_BURST_LEN: usize = 8;
 
 /// Represents the current state of an individual typing test.
 #[derive(Debug, Default, Clone, PartialEq)]
@@ -20,15 +45,110 @@ pub enum TypingStatus {
     Resuming,
     /// The typing test has completed
     Completed,
+    /// The typing test failed before it could complete
+    Failed,
+}
+
+/// Why a typing test failed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FailureReason {
+    /// A mistake was made on `master` difficulty
+    Mistake,
+    /// A word was submitted with errors on `expert` difficulty
+    WordWithErrors,
+    /// The live accuracy dropped below the minimum percentage
+    LowAccuracy(u8),
+    /// The live WPM dropped below the minimum
+    LowWpm(u16),
+}
+
+impl fmt::Display for FailureReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mistake => write!(f, "A mistake was made (master)"),
+            Self::WordWithErrors => write!(f, "A word was submitted with errors (expert)"),
+            Self::LowAccuracy(min) => write!(f, "Accuracy dropped below {min}%"),
+            Self::LowWpm(min) => write!(f, "WPM dropped below {min}"),
+        }
+    }
+}
+
+/// Why a finished typing test shouldn't be trusted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValidityIssue {
+    /// The test was too short to be meaningful
+    TooShort,
+    /// There was a long gap without any keystroke
+    Idle,
+    /// The test was typed faster than physically plausible
+    TooFast,
+    /// The time between keystrokes was too regular to come from a person
+    UniformTiming,
+    /// Part of the text arrived at once, as if pasted
+    Pasted,
+}
+
+impl fmt::Display for ValidityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort => write!(f, "too short"),
+            Self::Idle => write!(f, "idle for too long"),
+            Self::TooFast => write!(f, "implausibly fast"),
+            Self::UniformTiming => write!(f, "implausibly uniform timing"),
+            Self::Pasted => write!(f, "pasted input"),
+        }
+    }
+}
+
+/// Assessment of whether a test reflects actual typing. Invalid tests are still saved but they
+/// don't count towards personal bests or the leaderboard ranking
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TestValidity {
+    pub issues: Vec<ValidityIssue>,
+}
+
+impl TestValidity {
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn flag(&mut self, issue: ValidityIssue, condition: bool) {
+        if condition {
+            self.issues.push(issue);
+        }
+    }
 }
 
-/// Contains typing information about each token/chacter. A `Word` is composed of one or more `Tokens`
+impl fmt::Display for TestValidity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let issues: Vec<String> = self.issues.iter().map(|i| i.to_string()).collect();
+        write!(f, "{}", issues.join(", "))
+    }
+}
+
+/// The kind of a token. Line breaks and the indentation that follows them are preserved for
+/// multi-line texts such as code snippets
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum TokenKind {
+    #[default]
+    /// A regular character, `<space>` included
+    Char,
+    /// A hard line break
+    Newline,
+    /// Leading whitespace of a line. Typed automatically after a line break
+    Indent,
+}
+
+/// Contains typing information about each token/grapheme. A `Word` is composed of one or more `Tokens`
 #[derive(Debug, Clone)]
 pub struct Token {
-    /// The typed token(char)
-    pub typed: Option<char>,
+    /// What was typed for the token
+    pub typed: Option<Grapheme>,
     /// The actual expected token
-    pub target: char,
+    pub target: Grapheme,
+    /// The kind of the token
+    pub kind: TokenKind,
     /// Wether this token was typed wrong or not
     pub is_wrong: bool,
     /// Whether this token was skipped during space jump or not
@@ -39,14 +159,31 @@ pub struct Token {
 
 impl Token {
     pub fn is_extra_token(&self) -> bool {
-        self.is_wrong && self.target == self.typed.unwrap_or('\0') && self.target != ' '
+        self.is_wrong && self.typed.as_ref() == Some(&self.target) && !self.is_separator()
+    }
+
+    /// Whether this token separates two words, either a `<space>` or a hard line break
+    pub fn is_separator(&self) -> bool {
+        self.target.is_separator()
+    }
+
+    /// Whether this token was typed and it matched its target
+    fn is_typed_correctly(&self) -> bool {
+        self.typed.is_some() && !self.is_wrong && !self.is_skipped
     }
 
     fn is_correct_non_space_token(&self) -> bool {
-        self.target != ' ' && self.typed == Some(self.target) && !self.is_wrong
+        !self.is_separator() && self.typed.is_some() && !self.is_wrong
     }
 }
 
+/// Whether the given character separates two words. Documents keep their line breaks, so `\n` is
+/// a separator just like `<space>` is.
+#[inline]
+pub fn is_separator(c: char) -> bool {
+    c == ' ' || c == '\n'
+}
+
 /// Contains information about a word present the the typing test word pool
 #[derive(Debug, Clone)]
 pub struct Word {
@@ -62,6 +199,95 @@ pub struct Word {
     pub completed: bool,
 }
 
+impl Word {
+    /// Speed the word was typed at, from the end of the previous word to the end of this one
+    pub fn burst_wpm(&self) -> Option<f64> {
+        if !self.completed {
+            return None;
+        }
+        let secs = self
+            .end_time?
+            .duration_since(self.start_time?)
+            .as_secs_f64();
+        let chars = grapheme::graphemes(&self.target).count() as f64;
+        Some((chars / 5.0) / (secs.max(0.1) / 60.0))
+    }
+}
+
+/// Burst WPM of a completed word and when the word was completed, in seconds since the start
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WordBurst {
+    pub at_secs: f64,
+    pub wpm: f64,
+}
+
+/// Lifetime keystroke counters of a test. Unlike `typed_text`, which forgets whatever gets
+/// backspaced, these only ever grow, so corrected mistakes still show up
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CharStats {
+    /// Keystrokes that typed the expected character
+    pub correct: usize,
+    /// Keystrokes that typed a wrong character, those rejected by the error policy included
+    pub incorrect: usize,
+    /// Wrong keystrokes that were backspaced afterwards
+    pub corrected: usize,
+    /// Keystrokes typed past the end of a word
+    pub extra: usize,
+    /// Characters skipped by jumping to the next word
+    pub missed: usize,
+}
+
+impl CharStats {
+    /// Number of character keystrokes, right or wrong
+    #[inline]
+    pub fn typed(&self) -> usize {
+        self.correct + self.mistakes()
+    }
+
+    /// Number of wrong character keystrokes
+    #[inline]
+    pub fn mistakes(&self) -> usize {
+        self.incorrect + self.extra
+    }
+
+    /// Share of keystrokes that were right the first time
+    pub fn accuracy(&self) -> f64 {
+        let total = self.typed();
+        if total > 0 {
+            self.correct as f64 / total as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Monkeytype style `correct/incorrect/extra/missed`
+impl fmt::Display for CharStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}/{}/{}",
+            self.correct, self.incorrect, self.extra, self.missed
+        )
+    }
+}
+
+/// A key press fed to the typing test
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum KeystrokeKind {
+    Char(char),
+    Backspace,
+    /// Ctrl+Backspace and friends, see `Tracker::delete_word`
+    DeleteWord,
+}
+
+/// A key press and when it happened, in milliseconds since the test started
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Keystroke {
+    pub at_ms: u64,
+    pub kind: KeystrokeKind,
+}
+
 /// Represents a space jump operation
 #[derive(Debug, Clone, Copy, PartialEq)]
 struct SpaceJump {
@@ -71,6 +297,27 @@ struct SpaceJump {
     target_pos: usize,
 }
 
+/// Behavioural options of a typing test that don't depend on its mode. Options missing from older
+/// recordings take their default
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackerOptions {
+    /// Closing brackets are typed automatically once their opening bracket was typed correctly
+    pub auto_close_brackets: bool,
+    /// How wrong keystrokes are handled
+    pub error_policy: ErrorPolicy,
+    /// Which mistakes fail the test
+    pub difficulty: Difficulty,
+    /// Minimum live accuracy (percentage) before the test fails. `0` disables it
+    pub min_accuracy: u8,
+    /// Minimum live WPM before the test fails. `0` disables it
+    pub min_wpm: u16,
+    /// Precomposed and decomposed characters are the same thing, see `Grapheme::is_typed_by`
+    pub normalize: bool,
+    /// Diacritics are optional, `a` types `á`. What can be left out depends on `Tracker::folding`
+    pub lazy: bool,
+}
+
 impl SpaceJump {
     #[inline]
     const fn new(source_pos: usize, target_pos: usize) -> Self {
@@ -121,6 +368,26 @@ pub struct Tracker {
     wpm_snapshots: WpmSnapshots,
     /// Last time a WPM sample snapshot was taken
     last_snapshot_time: Option<Instant>,
+    /// Test time and keystroke counters at the last per second sample
+    last_sample: (Duration, CharStats),
+    /// Options the test runs with
+    pub options: TrackerOptions,
+    /// Number of tokens typed automatically (indentation, auto-closed brackets)
+    auto_typed_count: usize,
+    /// Number of keystrokes rejected by the error policy
+    rejected_count: usize,
+    /// Why the test failed, if it did
+    pub failure: Option<FailureReason>,
+    /// Every keystroke that reached the test, in order
+    pub keystrokes: Vec<Keystroke>,
+    /// Keystroke counters of the whole test, backspaced keystrokes included
+    pub char_stats: CharStats,
+    /// Events not handed out yet, see `take_events`
+    events: Vec<TrackerEvent>,
+    /// Keystrokes typed so far for the current token when its grapheme takes more than one
+    pending: String,
+    /// Diacritic folding table of the text language, used by lazy tests
+    pub folding: Folding,
 }
 
 impl Tracker {
@@ -149,11 +416,98 @@ impl Tracker {
             wpm_snapshots: WpmSnapshots::new(),
             extra_errors_count: 0,
             last_snapshot_time: None,
+            last_sample: (Duration::ZERO, CharStats::default()),
+            options: TrackerOptions::default(),
+            auto_typed_count: 0,
+            rejected_count: 0,
+            failure: None,
+            keystrokes: Vec::new(),
+            char_stats: CharStats::default(),
+            events: Vec::new(),
+            pending: String::new(),
+            folding: Folding::default(),
         }
     }
 
+    pub fn with_options(mut self, options: TrackerOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_folding(mut self, folding: Folding) -> Self {
+        self.folding = folding;
+        self
+    }
+
+    /// Resets the tracker for a new test. The current options and folding table are kept
     pub fn reset(&mut self, text: String, mode: Mode) {
-        *self = Self::new(text, mode);
+        // whatever happened before the reset still has to reach the observers
+        let events = std::mem::take(&mut self.events);
+        let folding = std::mem::take(&mut self.folding);
+        *self = Self::new(text, mode)
+            .with_options(self.options)
+            .with_folding(folding);
+        self.events = events;
+        self.emit(TrackerEvent::Reset);
+    }
+
+    /// Hands out the events that happened since the last call, oldest first
+    pub fn take_events(&mut self) -> Vec<TrackerEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    #[inline]
+    fn emit(&mut self, event: TrackerEvent) {
+        self.events.push(event);
+    }
+
+    /// Appends more words to the end of the target text, i.e to keep time tests going. The test
+    /// carries on as is, only the text ahead of it grows
+    pub fn extend_text(&mut self, more: &str) {
+        if more.is_empty() {
+            return;
+        }
+        if self.text.chars().last().is_some_and(|c| !is_separator(c)) {
+            self.text.push(' ');
+            self.tokens.extend(Self::build_tokens(" "));
+        }
+
+        // boundaries are token positions, extra tokens included
+        let offset = self.tokens.len();
+        self.word_boundaries.extend(
+            Self::build_word_boundaries(more)
+                .into_iter()
+                .map(|boundary| boundary + offset),
+        );
+        self.words.extend(Self::build_words(more));
+        self.tokens.extend(Self::build_tokens(more));
+        self.text.push_str(more);
+        self.emit(TrackerEvent::TextExtended);
+    }
+
+    /// Appends text without separators to the last word, for tests typed without spaces (the
+    /// nospace funbox)
+    pub fn extend_last_word(&mut self, more: &str) {
+        let Some(word) = self.words.last_mut() else {
+            return self.extend_text(more);
+        };
+        if more.is_empty() {
+            return;
+        }
+        word.target.push_str(more);
+        self.tokens.extend(Self::build_tokens(more));
+        self.text.push_str(more);
+        self.emit(TrackerEvent::TextExtended);
+    }
+
+    /// Number of words from the current one to the end of the text
+    pub fn remaining_words(&self) -> usize {
+        self.words.len().saturating_sub(self.current_word_idx)
+    }
+
+    /// Number of characters from the cursor to the end of the text
+    pub fn remaining_chars(&self) -> usize {
+        self.tokens.len().saturating_sub(self.current_pos)
     }
 
     fn build_words(text: &str) -> Vec<Word> {
@@ -171,18 +525,32 @@ impl Tracker {
     }
 
     fn build_tokens(text: &str) -> Vec<Token> {
-        text.chars()
-            .map(|chr| Token {
-                typed: None,
-                target: chr,
-                is_wrong: false,
-                is_skipped: false,
-                typed_at: None,
+        let mut at_line_start = false;
+        grapheme::graphemes(text)
+            .map(|target| {
+                let kind = if target == '\n' {
+                    at_line_start = true;
+                    TokenKind::Newline
+                } else if at_line_start && target == ' ' {
+                    TokenKind::Indent
+                } else {
+                    at_line_start = false;
+                    TokenKind::Char
+                };
+                Token {
+                    typed: None,
+                    target,
+                    kind,
+                    is_wrong: false,
+                    is_skipped: false,
+                    typed_at: None,
+                }
             })
             .collect()
     }
 
-    /// Bulids the word boundaries vec, basically all the position where each word starts after spce
+    /// Bulids the word boundaries vec, basically all the token positions where each word starts
+    /// after a separator. Consecutive separators (i.e paragraph breaks) only yield one boundary.
     fn build_word_boundaries(text: &str) -> Vec<usize> {
         let capacity =
             (text.len() / WORD_BOUNDARY_ESTIMATE_RATIO).max(DEFAULT_WORD_BOUNDARY_CAPACITY);
@@ -190,25 +558,37 @@ impl Tracker {
 
         boundaries.push(0); // initial pos
 
-        let mut search_pos = 0;
-        while let Some(space_pos) = text[search_pos..].find(' ') {
-            let word_start = search_pos + space_pos + 1;
-            if word_start < text.len() {
-                boundaries.push(word_start);
-                search_pos = word_start;
-            } else {
-                break;
+        let mut prev_is_separator = false;
+        for (pos, g) in grapheme::graphemes(text).enumerate() {
+            let is_sep = g.is_separator();
+            if prev_is_separator && !is_sep {
+                boundaries.push(pos);
             }
+            prev_is_separator = is_sep;
         }
 
         boundaries.shrink_to_fit();
         boundaries
     }
 
+    /// Shifts the word boundaries after `pos` to account for tokens added or removed mid test
+    fn shift_word_boundaries(&mut self, pos: usize, inserted: bool) {
+        let start = self
+            .word_boundaries
+            .partition_point(|&boundary| boundary <= pos);
+        for boundary in &mut self.word_boundaries[start..] {
+            if inserted {
+                *boundary += 1;
+            } else {
+                *boundary -= 1;
+            }
+        }
+    }
+
     pub fn start_typing(&mut self) {
         if matches!(
             self.status,
-            TypingStatus::NotStarted | TypingStatus::Completed
+            TypingStatus::NotStarted | TypingStatus::Completed | TypingStatus::Failed
         ) {
             let now = Instant::now();
             self.status = TypingStatus::InProgress;
@@ -216,6 +596,7 @@ impl Tracker {
             if let Some(word) = self.current_word_mut() {
                 word.start_time = Some(now);
             }
+            self.emit(TrackerEvent::TestStarted);
             self.invalidate_metrics_cache();
             self.update_metrics();
             // NOTE: maybe we want a configurable option to let the user determine this behavior
@@ -233,6 +614,7 @@ impl Tracker {
         }
         self.paused_at = None;
         self.status = TypingStatus::InProgress;
+        self.emit(TrackerEvent::Resumed);
     }
 
     pub fn pause(&mut self) {
@@ -244,7 +626,8 @@ impl Tracker {
             self.total_paused_time += Instant::now().duration_since(paused_at);
         }
         self.status = TypingStatus::Paused;
-        self.paused_at = Some(Instant::now())
+        self.paused_at = Some(Instant::now());
+        self.emit(TrackerEvent::Paused);
     }
 
     pub fn unpause(&mut self) {
@@ -265,15 +648,21 @@ impl Tracker {
     }
 
     pub fn type_char(&mut self, c: char) -> Result<(), AppError> {
-        let is_space = c == ' ';
+        let is_space = is_separator(c);
         // never the first character of the test will be a space character.
         if self.is_idle() && is_space {
             return Err(AppError::IllegalSpaceCharacter);
         }
 
+        // Enter only types line breaks in texts that have them, elsewhere it's not a space
+        if c == '\n' && !self.mode.is_zen_mode() && !self.has_line_breaks() {
+            return Err(AppError::IllegalSpaceCharacter);
+        }
+
         // never the first character of a word is a space char, so if space is typed at the start
         // of a word do absolutely nothing. This is what monkeytype does anyways.
-        if is_space && self.is_at_word_start() {
+        // The only exception are paragraph breaks, where the second line break is expected.
+        if is_space && self.is_at_word_start() && self.current_target_char() != Some(c) {
             return Err(AppError::IllegalSpaceCharacter);
         }
 
@@ -295,23 +684,50 @@ impl Tracker {
             return Err(AppError::TypingTestAlreadyCompleted);
         }
 
-        // this is the actual expected(target) character we are typing against
-        let expected_char = self
+        self.record_keystroke(KeystrokeKind::Char(c));
+
+        if self.mode.is_zen_mode() {
+            // there is no target to get wrong
+            self.char_stats.correct += 1;
+            self.emit(TrackerEvent::CharTyped { c, correct: true });
+            self.type_zen_char(c);
+            return Ok(());
+        }
+
+        // this is the actual expected(target) grapheme we are typing against
+        let expected = self
             .current_token()
             .ok_or(AppError::InvalidCharacterPosition)?
-            .target;
+            .target
+            .clone();
+        // graphemes made of more than one code point take more than one keystroke
+        let input = format!("{}{c}", self.pending);
+        let is_correct = self.is_typed_by(&expected, &input);
+
+        // strict error policies swallow the keystroke, the cursor stays where it is
+        if !self.is_keystroke_allowed(c, &input, &expected) {
+            self.rejected_count += 1;
+            self.char_stats.incorrect += 1;
+            self.emit(TrackerEvent::CharRejected(c));
+            self.check_keystroke_failure(true, false);
+            return Ok(());
+        }
 
         // add wrong tokens at word boundary. Monkey see, monkey do...
-        if expected_char == ' ' && !is_space {
+        if expected.is_separator() && !is_space {
+            self.char_stats.extra += 1;
+            self.emit(TrackerEvent::CharTyped { c, correct: false });
             if self.extra_errors_count < MAX_EXTRA_WRONG_CHARS {
                 let new_token = Token {
-                    typed: Some(c),
-                    target: c,
+                    typed: Some(Grapheme::from(c)),
+                    target: Grapheme::from(c),
+                    kind: TokenKind::Char,
                     is_wrong: true,
                     is_skipped: false,
                     typed_at: Some(Instant::now()),
                 };
                 self.tokens.insert(self.current_pos, new_token);
+                self.shift_word_boundaries(self.current_pos, true);
                 self.typed_text.push(c);
                 self.total_errors += 1;
                 // Note: we don't increment word.error_count for extra tokens at word boundaries
@@ -319,28 +735,40 @@ impl Tracker {
                 self.extra_errors_count += 1;
                 self.current_pos += 1;
             }
+            self.check_keystroke_failure(true, false);
             return Ok(());
         }
 
         // space jumping shenanigans
         if is_space
-            && expected_char != ' '
+            && !expected.is_separator()
             && let Some(target_pos) = self.calculate_space_jump_target()
         {
-            return self.perform_space_jump(target_pos);
+            self.pending.clear();
+            return self.perform_space_jump(target_pos, c);
+        }
+
+        // the grapheme isn't finished yet, the cursor stays on it until it is
+        if !is_space && !is_correct && expected.expects_more(&input, self.options.normalize) {
+            self.pending = input;
+            return Ok(());
         }
+        self.pending.clear();
 
         // upate current token information
         if let Some(token) = self.current_token_mut() {
-            token.typed = Some(c);
+            token.typed = Some(Grapheme::from(input.as_str()));
             token.typed_at = Some(Instant::now());
-            token.is_wrong = expected_char != c;
+            token.is_wrong = !is_correct;
         }
 
-        self.typed_text.push(c);
+        self.typed_text.push_str(&input);
 
         // errror tracking
-        if expected_char != c {
+        if is_correct {
+            self.char_stats.correct += 1;
+        } else {
+            self.char_stats.incorrect += 1;
             self.total_errors += 1;
             if let Some(word) = self.current_word_mut() {
                 word.error_count += 1;
@@ -348,11 +776,19 @@ impl Tracker {
         }
 
         self.current_pos += 1;
+        self.emit(TrackerEvent::CharTyped {
+            c,
+            correct: is_correct,
+        });
 
-        if self.should_mark_word_as_completed() {
+        let word_submitted = self.should_mark_word_as_completed();
+        if word_submitted {
             self.mark_word_as_completed();
         }
 
+        self.check_keystroke_failure(!is_correct, word_submitted);
+        self.auto_type_tokens();
+
         // if self.should_complete() {
         //     self.complete();
         // }
@@ -360,6 +796,91 @@ impl Tracker {
         Ok(())
     }
 
+    fn record_keystroke(&mut self, kind: KeystrokeKind) {
+        let at_ms = self.elapsed_time().as_millis() as u64;
+        self.keystrokes.push(Keystroke { at_ms, kind });
+    }
+
+    /// Fails the test if the last keystroke broke the rules of the current difficulty
+    fn check_keystroke_failure(&mut self, is_mistake: bool, word_submitted: bool) {
+        let reason = match self.options.difficulty {
+            Difficulty::Master if is_mistake => FailureReason::Mistake,
+            Difficulty::Expert
+                if word_submitted
+                    && !self
+                        .prev_word_tokens()
+                        .iter()
+                        .all(Token::is_typed_correctly) =>
+            {
+                FailureReason::WordWithErrors
+            }
+            _ => return,
+        };
+        self.fail(reason);
+    }
+
+    /// Fails the test once the live metrics drop below the configured minimums
+    fn check_threshold_failure(&mut self) {
+        if self.elapsed_time() < Duration::from_secs(FAILURE_THRESHOLD_GRACE_SECS) {
+            return;
+        }
+
+        let min_accuracy = self.options.min_accuracy;
+        let min_wpm = self.options.min_wpm;
+        let accuracy = self.metrics.accuracy.unwrap_or(0.0) * 100.0;
+        let wpm = self.metrics.wpm.unwrap_or(0.0);
+
+        if min_accuracy > 0 && accuracy < min_accuracy as f64 {
+            self.fail(FailureReason::LowAccuracy(min_accuracy));
+        } else if min_wpm > 0 && wpm < min_wpm as f64 {
+            self.fail(FailureReason::LowWpm(min_wpm));
+        }
+    }
+
+    /// Whether the error policy lets the keystroke `c` through when `expected` is the target.
+    /// `input` is everything typed for the current token so far, `c` included
+    fn is_keystroke_allowed(&self, c: char, input: &str, expected: &Grapheme) -> bool {
+        let on_track = self.is_typed_by(expected, input)
+            || expected.expects_more(input, self.options.normalize);
+        match self.options.error_policy {
+            ErrorPolicy::Normal => true,
+            ErrorPolicy::StopOnLetter => on_track,
+            ErrorPolicy::StopOnWord => {
+                // submitting a word (or the whole test) requires it to be correct
+                let is_last_token = self.current_pos + 1 >= self.tokens.len();
+                if is_separator(c) || is_last_token {
+                    on_track && self.is_current_word_correct()
+                } else {
+                    true
+                }
+            }
+        }
+    }
+
+    /// Whether `input` types the `expected` grapheme. Lazy tests also take it without diacritics
+    fn is_typed_by(&self, expected: &Grapheme, input: &str) -> bool {
+        let normalize = self.options.normalize;
+        expected.is_typed_by(input, normalize)
+            || (self.options.lazy
+                && self
+                    .folding
+                    .fold(expected)
+                    .is_some_and(|folded| folded.is_typed_by(input, normalize)))
+    }
+
+    /// Whether every token typed so far in the current word is correct
+    fn is_current_word_correct(&self) -> bool {
+        let start = self
+            .word_boundaries
+            .get(self.current_word_idx)
+            .copied()
+            .unwrap_or(self.current_pos)
+            .min(self.current_pos);
+        self.tokens[start..self.current_pos]
+            .iter()
+            .all(Token::is_typed_correctly)
+    }
+
     pub fn backspace(&mut self) -> Result<(), AppError> {
         // resume the test if paused or in refractory state after unpausing
         if self.is_resuming() || self.is_paused() {
@@ -374,15 +895,77 @@ impl Tracker {
             return Err(AppError::IllegalBackspace);
         }
 
+        self.record_keystroke(KeystrokeKind::Backspace);
+        self.emit(TrackerEvent::Backspace);
+        self.step_back()
+    }
+
+    /// Deletes the current word back to its start, or the previous word along with the
+    /// separators behind the cursor when the cursor is at a word boundary. Stops wherever a
+    /// backspace would, i.e it never goes back into a correctly typed completed word
+    pub fn delete_word(&mut self) -> Result<(), AppError> {
+        if self.is_resuming() || self.is_paused() {
+            self.resume();
+        }
+
+        if !self.is_typing() {
+            return Err(AppError::TypingTestNotInProgress);
+        }
+
+        if self.current_pos == 0 && self.pending.is_empty() {
+            return Err(AppError::IllegalBackspace);
+        }
+
+        self.record_keystroke(KeystrokeKind::DeleteWord);
+        self.emit(TrackerEvent::WordDeleted);
+
+        let mut deleted_word_chars = false;
+        loop {
+            // not `is_previous_token_a_separator`, in zen the caret is past the last token
+            let at_word_start =
+                self.prev_token().is_some_and(Token::is_separator) && self.pending.is_empty();
+            if self.current_pos == 0 && self.pending.is_empty()
+                || at_word_start && deleted_word_chars
+            {
+                return Ok(());
+            }
+
+            let before = (self.current_pos, self.pending.len());
+            deleted_word_chars |= !at_word_start;
+            self.step_back()?;
+            // the previous word is typed correctly, there is nothing else to take back
+            if (self.current_pos, self.pending.len()) == before {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Takes back the last keystroke that reached the text, the part of `backspace` that
+    /// `delete_word` repeats
+    fn step_back(&mut self) -> Result<(), AppError> {
+        if self.mode.is_zen_mode() {
+            self.zen_backspace();
+            return Ok(());
+        }
+
+        // takes back a keystroke of a grapheme that wasn't finished
+        if self.pending.pop().is_some() {
+            return Ok(());
+        }
+
+        // auto typed tokens go away along with the token that triggered them
+        self.untype_auto_tokens();
+
         // disallow backspace at word boundary after a correctly typed word,
         // but allow backspacing over space-jumped words or words with extra tokens
-        if self.is_previous_token_a_space()
+        if self.is_previous_token_a_separator()
             && let Some(prev_word) = self.prev_word()
             && prev_word.completed
             && prev_word.error_count == 0
             && !self.prev_word_has_skipped_tokens()
             && !self.prev_word_has_extra_tokens()
         {
+            self.auto_type_tokens();
             return Ok(());
         }
 
@@ -393,24 +976,27 @@ impl Tracker {
             return self.undo_space_jump(jump);
         }
 
-        self.typed_text.pop();
-
         self.current_pos -= 1;
+        self.untype_text(self.current_pos);
 
         // are we currently backspacing over an *extra* wrong token question mark
         if let Some(token) = self.tokens.get(self.current_pos)
             && token.is_extra_token()
         {
             self.tokens.remove(self.current_pos);
+            self.shift_word_boundaries(self.current_pos, false);
             self.total_errors = self.total_errors.saturating_sub(1);
             self.extra_errors_count = self.extra_errors_count.saturating_sub(1);
+            self.char_stats.corrected += 1;
             return Ok(()); // do not process the extra token
         }
 
-        // if we are backspacing over a space that completed a word, unmark the word as completed
+        // if we are backspacing over a space that completed a word, unmark the word as completed.
+        // The second line break of a paragraph break doesn't complete anything
         if let Some(token) = self.current_token()
-            && token.target == ' '
+            && token.is_separator()
             && token.typed.is_some()
+            && !self.prev_token().is_some_and(Token::is_separator)
             && self.current_word_idx > 0
         {
             self.current_word_idx -= 1;
@@ -421,13 +1007,14 @@ impl Tracker {
         }
 
         if let Some(token) = self.current_token_mut() {
-            let was_wrong = token.target != token.typed.unwrap_or('\0');
+            let was_wrong = token.is_wrong;
             token.typed = None;
             token.typed_at = None;
             token.is_wrong = false;
             token.is_skipped = false;
 
             if was_wrong {
+                self.char_stats.corrected += 1;
                 self.total_errors = self.total_errors.saturating_sub(1);
                 if let Some(word) = self.current_word_mut() {
                     word.error_count = word.error_count.saturating_sub(1);
@@ -437,8 +1024,175 @@ impl Tracker {
         Ok(())
     }
 
+    /// Types the tokens the user is not expected to type: the indentation after a line break and,
+    /// if enabled, the closing brackets whose opening bracket was typed correctly.
+    fn auto_type_tokens(&mut self) {
+        while self.is_auto_typed_at(self.current_pos) {
+            let now = Instant::now();
+            if let Some(token) = self.current_token_mut() {
+                token.typed = Some(token.target.clone());
+                token.typed_at = Some(now);
+                token.is_wrong = false;
+                let target = token.target.clone();
+                self.typed_text.push_str(target.as_str());
+            }
+            self.auto_typed_count += 1;
+            self.current_pos += 1;
+        }
+    }
+
+    /// Reverts the auto typed tokens right behind the cursor, see `auto_type_tokens`
+    fn untype_auto_tokens(&mut self) {
+        while self.current_pos > 0
+            && self.tokens[self.current_pos - 1].is_typed_correctly()
+            && self.is_auto_typed_at(self.current_pos - 1)
+        {
+            self.current_pos -= 1;
+            self.untype_text(self.current_pos);
+            self.auto_typed_count -= 1;
+            if let Some(token) = self.current_token_mut() {
+                token.typed = None;
+                token.typed_at = None;
+            }
+        }
+    }
+
+    /// Drops whatever was typed for the token at the given position from the end of `typed_text`
+    fn untype_text(&mut self, pos: usize) {
+        let len = self
+            .tokens
+            .get(pos)
+            .and_then(|token| token.typed.as_ref())
+            .map_or(0, |typed| typed.as_str().len());
+        self.typed_text
+            .truncate(self.typed_text.len().saturating_sub(len));
+    }
+
+    /// Whether the token at the given position is typed by the tracker rather than the user
+    pub(crate) fn is_auto_typed_at(&self, pos: usize) -> bool {
+        let Some(token) = self.tokens.get(pos) else {
+            return false;
+        };
+        match token.kind {
+            TokenKind::Indent => true,
+            TokenKind::Newline => false,
+            TokenKind::Char => {
+                self.options.auto_close_brackets
+                    && self
+                        .matching_open_bracket(pos)
+                        .is_some_and(|open| self.tokens[open].is_typed_correctly())
+            }
+        }
+    }
+
+    /// Finds the opening bracket matching the closing bracket at the given position, if any
+    fn matching_open_bracket(&self, pos: usize) -> Option<usize> {
+        let close = &self.tokens.get(pos)?.target;
+        let open = match close.as_str() {
+            ")" => '(',
+            "]" => '[',
+            "}" => '{',
+            _ => return None,
+        };
+
+        let mut depth = 0;
+        for (i, token) in self.tokens[..pos].iter().enumerate().rev() {
+            if token.is_extra_token() {
+                continue;
+            }
+            if token.target == *close {
+                depth += 1;
+            } else if token.target == open {
+                if depth == 0 {
+                    return Some(i);
+                }
+                depth -= 1;
+            }
+        }
+        None
+    }
+
+    /// In zen mode there's no target text, whatever gets typed becomes the target itself and thus
+    /// every token is always correct.
+    fn type_zen_char(&mut self, c: char) {
+        let now = Instant::now();
+
+        if is_separator(c) {
+            self.mark_word_as_completed();
+        } else {
+            if self.is_at_word_start() {
+                self.words.push(Word {
+                    target: String::new(),
+                    start_time: Some(now),
+                    end_time: None,
+                    error_count: 0,
+                    completed: false,
+                });
+            }
+            if let Some(word) = self.current_word_mut() {
+                word.target.push(c);
+            }
+        }
+
+        self.text.push(c);
+        self.typed_text.push(c);
+        self.tokens.push(Token {
+            typed: Some(Grapheme::from(c)),
+            target: Grapheme::from(c),
+            kind: if c == '\n' {
+                TokenKind::Newline
+            } else {
+                TokenKind::Char
+            },
+            is_wrong: false,
+            is_skipped: false,
+            typed_at: Some(now),
+        });
+        self.current_pos += 1;
+    }
+
+    /// Removes the last typed token from the zen buffer. Unlike the other modes there's nothing
+    /// preventing going back into already completed words, as they can't be wrong.
+    fn zen_backspace(&mut self) {
+        let Some(token) = self.tokens.pop() else {
+            return;
+        };
+        self.text.pop();
+        self.typed_text.pop();
+        self.current_pos -= 1;
+
+        if token.is_separator() {
+            self.current_word_idx = self.current_word_idx.saturating_sub(1);
+            if let Some(word) = self.current_word_mut() {
+                word.completed = false;
+                word.end_time = None;
+            }
+        } else if let Some(word) = self.current_word_mut() {
+            word.target.pop();
+            if word.target.is_empty() {
+                self.words.pop();
+            }
+        }
+    }
+
+    /// Ends a zen test. Zen tests have no target text, so they only end when the user says so.
+    pub fn finish(&mut self) -> Result<(), AppError> {
+        if !self.mode.is_zen_mode() {
+            return Ok(());
+        }
+        if !self.in_progress() {
+            return Err(AppError::TypingTestNotInProgress);
+        }
+        if self.is_paused() || self.is_resuming() {
+            self.resume();
+        }
+        self.complete();
+        Ok(())
+    }
+
+    /// First code point of the current target grapheme
     pub fn current_target_char(&self) -> Option<char> {
-        self.current_token().map(|c| c.target)
+        self.current_token().map(|token| token.target.base())
     }
 
     pub fn is_idle(&self) -> bool {
@@ -463,8 +1217,13 @@ impl Tracker {
         matches!(self.status, TypingStatus::Resuming)
     }
 
+    /// Whether the test is over, either because it completed or because it failed
     pub fn is_complete(&self) -> bool {
-        matches!(self.status, TypingStatus::Completed)
+        matches!(self.status, TypingStatus::Completed | TypingStatus::Failed)
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self.status, TypingStatus::Failed)
     }
 
     pub fn check_completion(&mut self) -> bool {
@@ -476,11 +1235,14 @@ impl Tracker {
         false
     }
 
-    fn is_previous_token_a_space(&self) -> bool {
-        if let (Some(curr), Some(prev)) = (self.current_token(), self.prev_token()) {
-            return curr.target != ' ' && prev.target == ' ';
-        }
-        false
+    fn has_line_breaks(&self) -> bool {
+        self.tokens
+            .iter()
+            .any(|token| token.kind == TokenKind::Newline)
+    }
+
+    fn is_previous_token_a_separator(&self) -> bool {
+        self.current_token().is_some() && self.prev_token().is_some_and(Token::is_separator)
     }
 
     fn prev_token(&self) -> Option<&Token> {
@@ -490,60 +1252,41 @@ impl Tracker {
         self.tokens.get(self.current_pos - 1)
     }
 
-    fn prev_word_has_extra_tokens(&self) -> bool {
-        if self.current_pos == 0 {
-            return false;
+    /// Gets the tokens of the previous word, including the separators that follow it
+    fn prev_word_tokens(&self) -> &[Token] {
+        let end = self.current_pos.min(self.tokens.len());
+        let mut start = end;
+        while start > 0 && self.tokens[start - 1].is_separator() {
+            start -= 1;
         }
+        while start > 0 && !self.tokens[start - 1].is_separator() {
+            start -= 1;
+        }
+        &self.tokens[start..end]
+    }
 
-        let mut pos = self.current_pos - 1;
-        let mut found_space = false;
-        while pos > 0 {
-            if let Some(token) = self.tokens.get(pos) {
-                if token.target == ' ' {
-                    if found_space {
-                        break;
-                    } else {
-                        found_space = true;
-                    }
-                } else if token.is_extra_token() {
-                    return true;
-                }
-            }
-            pos -= 1;
-        }
-        false
+    fn prev_word_has_extra_tokens(&self) -> bool {
+        self.prev_word_tokens().iter().any(Token::is_extra_token)
     }
 
     fn prev_word_has_skipped_tokens(&self) -> bool {
-        if self.current_pos == 0 {
-            return false;
-        }
-
-        let mut pos = self.current_pos - 1;
-        while pos > 0 {
-            if let Some(token) = self.tokens.get(pos) {
-                if token.is_skipped {
-                    return true;
-                }
-                if token.target == ' ' {
-                    // space before the prev word
-                    break;
-                }
-            }
-            pos -= 1;
-        }
-        false
+        self.prev_word_tokens().iter().any(|token| token.is_skipped)
     }
 
     fn would_complete_word_at(&self, pos: usize) -> bool {
         if pos == 0 {
             return false;
         }
-        // a word is `completed` if the previous token is a `<space>` or we are at the end of word
-        self.tokens
-            .get(pos - 1)
-            .is_some_and(|token| token.target == ' ')
-            || pos >= self.tokens.len()
+        // a word is `completed` if the previous token is a separator or we are at the end of word
+        self.tokens.get(pos - 1).is_some_and(Token::is_separator) || pos >= self.tokens.len()
+    }
+
+    /// Index of the word the token at `pos` belongs to, separators count as part of the word
+    /// before them
+    pub fn word_index_at(&self, pos: usize) -> usize {
+        self.word_boundaries
+            .partition_point(|&boundary| boundary <= pos)
+            .saturating_sub(1)
     }
 
     /// Checks if the word at the given position contains errors or not
@@ -593,10 +1336,15 @@ impl Tracker {
             return false;
         }
         let curr_char = self.prev_token();
-        let is_space_x = curr_char.map_or_else(|| false, |c| c.target == ' ');
+        let is_space_x = curr_char.is_some_and(Token::is_separator);
         let is_end = self.current_pos >= self.tokens.len();
+        // consecutive separators, i.e paragraph breaks, only complete the word once
+        let is_past_word_start = self
+            .word_boundaries
+            .get(self.current_word_idx)
+            .is_none_or(|&word_start| self.current_pos > word_start);
 
-        is_space_x || is_end
+        (is_space_x && is_past_word_start) || is_end
     }
 
     // NOTE: i did this words end and start time because i think it would be nice to show in a
@@ -606,6 +1354,9 @@ impl Tracker {
             word.completed = true;
             word.end_time = Some(Instant::now());
         }
+        self.emit(TrackerEvent::WordCompleted {
+            index: self.current_word_idx,
+        });
         self.current_word_idx += 1;
         self.extra_errors_count = 0;
 
@@ -615,6 +1366,11 @@ impl Tracker {
     }
 
     pub fn should_complete(&self) -> bool {
+        // zen tests grow with every keystroke, the only way out is an explicit `finish`
+        if self.mode.is_zen_mode() {
+            return false;
+        }
+
         // all words are typed, should end test
         if self.current_pos >= self.tokens.len() {
             return true;
@@ -629,10 +1385,28 @@ impl Tracker {
                 }
             }
             Mode::Words(count) => self.current_word_idx >= count,
+            // quotes, snippets and drills are only done once every character has been typed
+            Mode::Quote(_) | Mode::Code | Mode::Practice(_) => false,
+            Mode::Zen => false,
         }
     }
 
     pub fn complete(&mut self) {
+        self.end_test();
+        self.status = TypingStatus::Completed;
+        self.emit(TrackerEvent::Completed);
+    }
+
+    /// Ends the test without completing it
+    fn fail(&mut self, reason: FailureReason) {
+        log_debug!("Test failed: {reason}");
+        self.end_test();
+        self.failure = Some(reason);
+        self.status = TypingStatus::Failed;
+        self.emit(TrackerEvent::Failed(reason));
+    }
+
+    fn end_test(&mut self) {
         self.update_metrics();
         let completion_time = Instant::now();
         self.end_time = Some(completion_time);
@@ -646,12 +1420,10 @@ impl Tracker {
 
         self.update_metrics();
         log_debug!("Wpm samples: {:?}", self.wpm_snapshots);
-
-        self.status = TypingStatus::Completed;
     }
 
     fn is_at_word_start(&self) -> bool {
-        self.current_pos == 0 || self.prev_token().is_some_and(|prev| prev.target == ' ')
+        self.current_pos == 0 || self.prev_token().is_some_and(Token::is_separator)
     }
 
     /// Calculates the target position for a space jump
@@ -666,8 +1438,9 @@ impl Tracker {
         Some(next_boundary)
     }
 
-    /// Activate the spacedrive and perform the space jump to the target location
-    fn perform_space_jump(&mut self, target_pos: usize) -> Result<(), AppError> {
+    /// Activate the spacedrive and perform the space jump to the target location. The skipped
+    /// tokens are filled with the typed separator
+    fn perform_space_jump(&mut self, target_pos: usize, separator: char) -> Result<(), AppError> {
         let source_pos = self.current_pos;
         let jump_length = target_pos.saturating_sub(source_pos);
 
@@ -679,9 +1452,15 @@ impl Tracker {
             .push(SpaceJump::new(source_pos, target_pos));
 
         self.total_errors += jump_length;
+        // the space was typed too early, the characters before the separator were never typed
+        self.char_stats.incorrect += 1;
+        self.char_stats.missed += jump_length.saturating_sub(1);
+        self.emit(TrackerEvent::SpaceJump {
+            skipped: jump_length.saturating_sub(1),
+        });
 
         // fill the offset with spaces
-        let spaces: String = " ".repeat(jump_length);
+        let spaces: String = separator.to_string().repeat(jump_length);
         self.typed_text.push_str(&spaces);
 
         // update the offset tokens and words states
@@ -689,7 +1468,7 @@ impl Tracker {
             self.current_pos = pos;
 
             if let Some(token) = self.current_token_mut() {
-                token.typed = Some(' ');
+                token.typed = Some(Grapheme::from(separator));
                 token.typed_at = Some(Instant::now());
                 token.is_wrong = true;
                 token.is_skipped = true;
@@ -704,10 +1483,14 @@ impl Tracker {
         self.current_pos = target_pos;
 
         // did the jump complted a word
-        if self.should_mark_word_as_completed() {
+        let word_submitted = self.should_mark_word_as_completed();
+        if word_submitted {
             self.mark_word_as_completed();
         }
 
+        self.check_keystroke_failure(true, word_submitted);
+        self.auto_type_tokens();
+
         Ok(())
     }
 
@@ -716,6 +1499,11 @@ impl Tracker {
         let positions_to_undo = jump.target_pos.saturating_sub(jump.source_pos);
 
         self.total_errors = self.total_errors.saturating_sub(positions_to_undo);
+        self.char_stats.missed = self
+            .char_stats
+            .missed
+            .saturating_sub(positions_to_undo.saturating_sub(1));
+        self.char_stats.corrected += 1;
 
         // remove the spaces added by the space jump
         let new_len = self.typed_text.len().saturating_sub(positions_to_undo);
@@ -783,9 +1571,12 @@ impl Tracker {
             wpm: self.metrics.wpm.unwrap_or(0.0),
             wps: self.metrics.wpm.unwrap_or(0.0) / 60.0,
             snapshots: self.wpm_snapshots.clone(),
+            word_bursts: self.word_bursts(),
             accuracy: self.metrics.accuracy.unwrap_or(0.0),
+            keystroke_accuracy: self.char_stats.accuracy(),
+            char_stats: self.char_stats,
             consistency: self.metrics.consistency.unwrap_or(0.0),
-            total_chars: self.text.len(),
+            total_chars: grapheme::graphemes(&self.text).count(),
             correct_chars: self.correct_chars_count(),
             total_errors: self.total_errors,
             elapsed_time: self.elapsed_time(),
@@ -794,13 +1585,76 @@ impl Tracker {
             total_words: self.words.len(),
             progress: self.progress(),
             is_completed: self.is_complete(),
+            failure: self.failure,
+            validity: self.validity(),
         }
     }
 
+    /// Checks the test for signs that it wasn't really typed: too short, idle, too fast, too
+    /// regular or pasted
+    pub fn validity(&self) -> TestValidity {
+        let mut validity = TestValidity::default();
+        let too_short = match self.mode {
+            Mode::Time(duration) => duration < MIN_VALID_SECONDS,
+            Mode::Words(count) => count < MIN_VALID_WORDS,
+            // texts that end when they are typed through are judged by how much was typed
+            Mode::Zen | Mode::Quote(_) | Mode::Code => self.words.len() < MIN_VALID_WORDS,
+            Mode::Practice(_) => false,
+        };
+        validity.flag(ValidityIssue::TooShort, too_short);
+
+        let elapsed_ms = self.elapsed_time().as_millis() as u64;
+        let intervals: Vec<u64> = self
+            .keystrokes
+            .windows(2)
+            .map(|pair| pair[1].at_ms.saturating_sub(pair[0].at_ms))
+            .collect();
+
+        // the time between the last keystroke and the end counts too, a time test can run out
+        // with nobody at the keyboard
+        let trailing_gap = self
+            .keystrokes
+            .last()
+            .map_or(0, |k| elapsed_ms.saturating_sub(k.at_ms));
+        let longest_gap = intervals
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(0)
+            .max(trailing_gap);
+        validity.flag(ValidityIssue::Idle, longest_gap > IDLE_GAP_MS);
+
+        let typed = (self.correct_chars_count() + self.total_errors) as f64;
+        let raw_wpm = if elapsed_ms > 0 {
+            (typed / 5.0) / (elapsed_ms as f64 / 60_000.0)
+        } else {
+            0.0
+        };
+        validity.flag(ValidityIssue::TooFast, raw_wpm > MAX_PLAUSIBLE_WPM);
+
+        validity.flag(ValidityIssue::UniformTiming, is_uniform(&intervals));
+
+        let pasted = intervals
+            .split(|&gap| gap > PASTE_INTERVAL_MS)
+            .any(|burst| burst.len() >= PASTE_BURST_LEN);
+        validity.flag(ValidityIssue::Pasted, pasted);
+
+        validity
+    }
+
     /// Returns the current test progress. Takes into consideration the test mode for the progress calculation
     pub fn progress(&self) -> f64 {
         match self.mode {
-            Mode::Words(_) => (self.current_pos as f64 / self.text.len() as f64).min(1.0),
+            Mode::Words(_) | Mode::Quote(_) | Mode::Code | Mode::Practice(_) => {
+                (self.current_pos as f64 / self.tokens.len() as f64).min(1.0)
+            }
+            Mode::Zen => {
+                if self.status == TypingStatus::Completed {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
             Mode::Time(total_seconds) => {
                 if self.status == TypingStatus::Completed {
                     1.0
@@ -814,6 +1668,54 @@ impl Tracker {
         }
     }
 
+    /// Returns the token position a caret typing at a steady `wpm` would be at by now
+    pub fn pace_position(&self, wpm: f64) -> usize {
+        let chars_per_sec = wpm * 5.0 / 60.0;
+        let pos = (self.elapsed_time().as_secs_f64() * chars_per_sec) as usize;
+        pos.min(self.tokens.len())
+    }
+
+    /// Target text of the words that were left with errors, space jumped words included. In the
+    /// order they appear and without repeats
+    pub fn missed_words(&self) -> Vec<String> {
+        let mut missed: Vec<String> = Vec::new();
+        // zen words are whatever was typed, they can't be missed
+        if self.mode.is_zen_mode() {
+            return missed;
+        }
+        for word in self.words.iter().filter(|word| word.error_count > 0) {
+            if !missed.contains(&word.target) {
+                missed.push(word.target.clone());
+            }
+        }
+        missed
+    }
+
+    /// Number of target text characters the cursor went past. Extra tokens don't count, so
+    /// positions of different runs over the same text can be compared
+    pub fn text_position(&self) -> usize {
+        let end = self.current_pos.min(self.tokens.len());
+        self.tokens[..end]
+            .iter()
+            .filter(|token| !token.is_extra_token())
+            .count()
+    }
+
+    /// Token index of the given target text position, skipping over the extra tokens of this run
+    pub fn token_index_of(&self, text_pos: usize) -> usize {
+        let mut remaining = text_pos;
+        for (idx, token) in self.tokens.iter().enumerate() {
+            if token.is_extra_token() {
+                continue;
+            }
+            if remaining == 0 {
+                return idx;
+            }
+            remaining -= 1;
+        }
+        self.tokens.len()
+    }
+
     /// Returns the elapsed time of the curren typin test
     pub fn elapsed_time(&self) -> Duration {
         let raw_elapsed = match (self.start_time, self.end_time) {
@@ -828,8 +1730,10 @@ impl Tracker {
         adjusted
     }
 
+    /// Returns the number of correctly typed characters. Auto typed tokens are not counted
     pub fn correct_chars_count(&self) -> usize {
-        self.typed_text.len() - self.total_errors
+        // every token behind the cursor was typed exactly once
+        self.current_pos - self.total_errors - self.auto_typed_count
     }
 
     /// Returns the number of correctly typed non-space characters so far
@@ -854,15 +1758,47 @@ impl Tracker {
             if current_wpm > 0.0 {
                 self.wpm_snapshots.push(current_wpm);
             }
+            let sample = self.sample_second(current_wpm);
+            self.wpm_snapshots.push_second(sample);
             self.last_snapshot_time = Some(now);
         }
 
         if self.should_update_metrics(now) {
             self.update_metrics();
             self.metrics.last_updated_at = Some(now);
+            self.check_threshold_failure();
         }
     }
 
+    /// Raw WPM and mistakes of the keystrokes typed since the last sample
+    fn sample_second(&mut self, wpm: f64) -> SecondSample {
+        let elapsed = self.elapsed_time();
+        let (last_elapsed, last_stats) = self.last_sample;
+        let secs = elapsed.saturating_sub(last_elapsed).as_secs_f64().max(0.1);
+        let typed = self.char_stats.typed() - last_stats.typed();
+        self.last_sample = (elapsed, self.char_stats);
+        SecondSample {
+            wpm,
+            raw_wpm: (typed as f64 / 5.0) / (secs / 60.0),
+            errors: self.char_stats.mistakes() - last_stats.mistakes(),
+        }
+    }
+
+    /// Burst WPM of every completed word, in the order they were typed
+    pub fn word_bursts(&self) -> Vec<WordBurst> {
+        let Some(start) = self.start_time else {
+            return Vec::new();
+        };
+        self.words
+            .iter()
+            .filter_map(|word| {
+                let wpm = word.burst_wpm()?;
+                let at_secs = word.end_time?.duration_since(start).as_secs_f64();
+                Some(WordBurst { at_secs, wpm })
+            })
+            .collect()
+    }
+
     fn should_snapshot_wpm(&self, now: Instant) -> bool {
         let elapsed = self.elapsed_time();
         elapsed >= Duration::from_secs(1)
@@ -901,7 +1837,8 @@ impl Tracker {
     }
 
     fn calculate_accuracy(&self) -> f64 {
-        let total_typed = self.typed_text.len() as f64;
+        // rejected keystrokes never reach the text but they are still mistakes
+        let total_typed = (self.current_pos - self.auto_typed_count + self.rejected_count) as f64;
         if total_typed > 0.0 {
             self.correct_chars_count() as f64 / total_typed
         } else {
@@ -938,7 +1875,12 @@ pub struct Summary {
     pub wpm: f64,
     pub wps: f64,
     pub snapshots: WpmSnapshots,
+    pub word_bursts: Vec<WordBurst>,
+    /// Accuracy of the final text, corrected mistakes don't count against it
     pub accuracy: f64,
+    /// Accuracy of every keystroke, corrected mistakes included
+    pub keystroke_accuracy: f64,
+    pub char_stats: CharStats,
     pub consistency: f64,
     pub total_chars: usize,
     pub total_words: usize,
@@ -949,6 +1891,8 @@ pub struct Summary {
     pub completed_words: usize,
     pub progress: f64,
     pub is_completed: bool,
+    pub failure: Option<FailureReason>,
+    pub validity: TestValidity,
 }
 
 impl Summary {
@@ -976,10 +1920,42 @@ impl Summary {
     }
 }
 
+/// Whether the keystroke intervals vary less than a person's would
+fn is_uniform(intervals: &[u64]) -> bool {
+    if intervals.len() < MIN_UNIFORM_SAMPLES {
+        return false;
+    }
+    let count = intervals.len() as f64;
+    let mean = intervals.iter().sum::<u64>() as f64 / count;
+    if mean <= 0.0 {
+        // all at once is a paste, not a metronome
+        return false;
+    }
+    let variance = intervals
+        .iter()
+        .map(|&gap| (gap as f64 - mean).powi(2))
+        .sum::<f64>()
+        / count;
+    variance.sqrt() / mean < MIN_TIMING_VARIATION
+}
+
+/// What happened during one second of a test
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SecondSample {
+    /// Net WPM of the test so far
+    pub wpm: f64,
+    /// WPM of the keystrokes typed during the second, mistakes included
+    pub raw_wpm: f64,
+    /// Wrong keystrokes typed during the second
+    pub errors: usize,
+}
+
 /// Snapshots of WPM sample taken periodically while typing
 #[derive(Debug, Clone, Default)]
 pub struct WpmSnapshots {
     snapshots: Vec<f64>,
+    /// One sample per second, unlike `snapshots` the seconds without any WPM are kept
+    seconds: Vec<SecondSample>,
 }
 
 impl WpmSnapshots {
@@ -993,6 +1969,17 @@ impl WpmSnapshots {
         self.snapshots.push(wpm);
     }
 
+    #[inline]
+    pub fn push_second(&mut self, sample: SecondSample) {
+        self.seconds.push(sample);
+    }
+
+    /// Returns the per second samples, the first one taken one second into the test
+    #[inline]
+    pub fn seconds(&self) -> &[SecondSample] {
+        &self.seconds
+    }
+
     /// Returns an iterator over the WPM snapshots
     #[inline]
     pub fn iter(&self) -> impl Iterator<Item = &f64> {
@@ -1057,6 +2044,7 @@ impl WpmSnapshots {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::QuoteLength;
 
     #[test]
     fn test_new_tracker() {
@@ -1274,7 +2262,10 @@ mod tests {
         tracker.type_char('f').unwrap();
         assert!(tracker.tokens.first().unwrap().is_wrong);
         assert!(tracker.tokens.first().unwrap().typed.is_some());
-        assert_eq!(tracker.tokens.first().unwrap().typed, Some('f'));
+        assert_eq!(
+            tracker.tokens.first().unwrap().typed,
+            Some(Grapheme::from('f'))
+        );
         assert_eq!(tracker.tokens.first().unwrap().target, 'h');
 
         tracker.backspace().unwrap();
@@ -1285,7 +2276,10 @@ mod tests {
         tracker.type_char('h').unwrap();
         assert!(!tracker.tokens.first().unwrap().is_wrong);
         assert!(tracker.tokens.first().unwrap().typed.is_some());
-        assert_eq!(tracker.tokens.first().unwrap().typed, Some('h'));
+        assert_eq!(
+            tracker.tokens.first().unwrap().typed,
+            Some(Grapheme::from('h'))
+        );
     }
 
     #[test]
@@ -1773,6 +2767,92 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_delete_word_clears_current_word() {
+        let mut tracker = Tracker::new("hello world".to_string(), Mode::with_words(2));
+        for c in "hello wxx".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.total_errors, 2);
+
+        tracker.delete_word().unwrap();
+        assert_eq!(tracker.current_pos, 6);
+        assert_eq!(tracker.current_word_idx, 1);
+        assert_eq!(tracker.total_errors, 0);
+        assert_eq!(tracker.char_stats.corrected, 2);
+        assert!(tracker.tokens[6..9].iter().all(|t| t.typed.is_none()));
+
+        // the previous word is correct, so there is nothing else to delete
+        tracker.delete_word().unwrap();
+        assert_eq!(tracker.current_pos, 6);
+        assert_eq!(tracker.current_word_idx, 1);
+        assert!(tracker.words[0].completed);
+    }
+
+    #[test]
+    fn test_delete_word_at_boundary_takes_back_previous_word() {
+        let mut tracker = Tracker::new("one two three".to_string(), Mode::with_words(3));
+        for c in "one twx ".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.current_word_idx, 2);
+
+        tracker.delete_word().unwrap();
+        assert_eq!(tracker.current_pos, 4);
+        assert_eq!(tracker.current_word_idx, 1);
+        assert!(!tracker.words[1].completed);
+        assert_eq!(tracker.words[1].error_count, 0);
+        assert_eq!(tracker.total_errors, 0);
+    }
+
+    #[test]
+    fn test_delete_word_undoes_extra_tokens_and_space_jumps() {
+        let mut tracker = Tracker::new("hello world".to_string(), Mode::with_words(2));
+        for c in "helloXY".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.total_errors, 2);
+
+        // the extra tokens belong to the word, so the whole word goes away
+        tracker.delete_word().unwrap();
+        assert_eq!(tracker.current_pos, 0);
+        assert_eq!(tracker.tokens.len(), "hello world".len());
+        assert_eq!(tracker.total_errors, 0);
+        assert_eq!(tracker.extra_errors_count, 0);
+
+        for c in "he ".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.current_pos, 6);
+        assert_eq!(tracker.space_jump_stack.len(), 1);
+
+        tracker.delete_word().unwrap();
+        assert_eq!(tracker.current_pos, 0);
+        assert_eq!(tracker.current_word_idx, 0);
+        assert!(tracker.space_jump_stack.is_empty());
+        assert!(!tracker.words[0].completed);
+        assert!(tracker.tokens.iter().all(|t| t.typed.is_none()));
+
+        assert!(tracker.delete_word().is_err());
+    }
+
+    #[test]
+    fn test_zen_mode_delete_word_stops_at_word_start() {
+        let mut tracker = Tracker::new(String::new(), Mode::Zen);
+        for c in "hello world".chars() {
+            tracker.type_char(c).unwrap();
+        }
+
+        tracker.delete_word().unwrap();
+        assert_eq!(tracker.typed_text, "hello ");
+        assert_eq!(tracker.words.len(), 1);
+
+        // at a boundary the space and the previous word go away
+        tracker.delete_word().unwrap();
+        assert!(tracker.typed_text.is_empty());
+        assert!(tracker.words.is_empty());
+    }
+
     #[test]
     fn test_complete_word_with_errors_then_backspace_and_fix() {
         let mut tracker = Tracker::new("hello world".to_string(), Mode::with_words(2));
@@ -1922,4 +3002,808 @@ mod tests {
         tracker.backspace().unwrap();
         assert_eq!(tracker.current_pos, 2);
     }
+
+    #[test]
+    fn test_zen_mode_builds_text_while_typing() {
+        let mut tracker = Tracker::new(String::new(), Mode::Zen);
+        assert!(tracker.tokens.is_empty());
+
+        for c in "hi there".chars() {
+            tracker.type_char(c).unwrap();
+        }
+
+        assert!(tracker.is_typing());
+        assert_eq!(tracker.text, "hi there");
+        assert_eq!(tracker.typed_text, "hi there");
+        assert_eq!(tracker.tokens.len(), 8);
+        assert_eq!(tracker.current_pos, 8);
+        assert_eq!(tracker.total_errors, 0);
+        assert_eq!(tracker.words.len(), 2);
+        assert!(tracker.words[0].completed);
+        assert_eq!(tracker.words[0].target, "hi");
+        assert_eq!(tracker.words[1].target, "there");
+        assert!(tracker.tokens.iter().all(|t| !t.is_wrong));
+    }
+
+    #[test]
+    fn test_zen_mode_rejects_leading_and_double_spaces() {
+        let mut tracker = Tracker::new(String::new(), Mode::Zen);
+        assert!(matches!(
+            tracker.type_char(' '),
+            Err(AppError::IllegalSpaceCharacter)
+        ));
+        tracker.type_char('a').unwrap();
+        tracker.type_char(' ').unwrap();
+        assert!(matches!(
+            tracker.type_char(' '),
+            Err(AppError::IllegalSpaceCharacter)
+        ));
+        assert_eq!(tracker.text, "a ");
+    }
+
+    #[test]
+    fn test_zen_mode_backspace_shrinks_buffer() {
+        let mut tracker = Tracker::new(String::new(), Mode::Zen);
+        for c in "ab c".chars() {
+            tracker.type_char(c).unwrap();
+        }
+
+        tracker.backspace().unwrap();
+        assert_eq!(tracker.text, "ab ");
+        assert_eq!(tracker.words.len(), 1);
+
+        // going back into a completed word is allowed in zen
+        tracker.backspace().unwrap();
+        assert_eq!(tracker.text, "ab");
+        assert_eq!(tracker.current_word_idx, 0);
+        assert!(!tracker.words[0].completed);
+
+        tracker.backspace().unwrap();
+        tracker.backspace().unwrap();
+        assert!(tracker.text.is_empty());
+        assert!(tracker.tokens.is_empty());
+        assert!(tracker.words.is_empty());
+        assert!(tracker.backspace().is_err());
+    }
+
+    #[test]
+    fn test_zen_mode_only_completes_on_finish() {
+        let mut tracker = Tracker::new(String::new(), Mode::Zen);
+        assert!(tracker.finish().is_err());
+
+        for c in "free typing".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert!(!tracker.check_completion());
+        assert!(!tracker.is_complete());
+
+        tracker.finish().unwrap();
+        assert!(tracker.is_complete());
+        let summary = tracker.summary();
+        assert_eq!(summary.completed_words, 2);
+        assert_eq!(summary.correct_chars, 11);
+        assert_eq!(summary.progress, 1.0);
+    }
+
+    #[test]
+    fn test_finish_is_a_noop_outside_zen_mode() {
+        let mut tracker = Tracker::new("hello".to_string(), Mode::with_words(1));
+        tracker.type_char('h').unwrap();
+        tracker.finish().unwrap();
+        assert!(!tracker.is_complete());
+    }
+
+    #[test]
+    fn test_word_boundaries_respect_line_breaks() {
+        let boundaries = Tracker::build_word_boundaries("hi there\nnew line\n\nnext");
+        assert_eq!(boundaries, vec![0, 3, 9, 13, 19]);
+    }
+
+    #[test]
+    fn test_word_boundaries_use_char_positions() {
+        let boundaries = Tracker::build_word_boundaries("año más");
+        assert_eq!(boundaries, vec![0, 4]);
+    }
+
+    #[test]
+    fn test_word_index_at() {
+        let tracker = Tracker::new("hi there\n\nyou".to_string(), Mode::with_words(3));
+        assert_eq!(tracker.word_index_at(0), 0);
+        assert_eq!(tracker.word_index_at(2), 0);
+        assert_eq!(tracker.word_index_at(3), 1);
+        assert_eq!(tracker.word_index_at(9), 1);
+        assert_eq!(tracker.word_index_at(10), 2);
+    }
+
+    #[test]
+    fn test_line_break_completes_word() {
+        let mut tracker = Tracker::new("one\ntwo".to_string(), Mode::with_words(2));
+        for c in "one".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        tracker.type_char('\n').unwrap();
+        assert_eq!(tracker.current_word_idx, 1);
+        assert!(tracker.words[0].completed);
+        assert_eq!(tracker.words[0].error_count, 0);
+        assert_eq!(tracker.current_target_char(), Some('t'));
+    }
+
+    #[test]
+    fn test_paragraph_break_completes_word_once() {
+        let mut tracker = Tracker::new("one\n\ntwo three".to_string(), Mode::with_words(3));
+        for c in "one".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        tracker.type_char('\n').unwrap();
+        assert_eq!(tracker.current_word_idx, 1);
+
+        // the blank line is expected, but only a line break can be typed there
+        assert!(tracker.type_char(' ').is_err());
+        tracker.type_char('\n').unwrap();
+        assert_eq!(tracker.current_word_idx, 1);
+        assert_eq!(tracker.current_target_char(), Some('t'));
+
+        for c in "two ".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.current_word_idx, 2);
+        assert_eq!(tracker.total_errors, 0);
+    }
+
+    #[test]
+    fn test_space_at_line_break_is_wrong() {
+        let mut tracker = Tracker::new("one\ntwo".to_string(), Mode::with_words(2));
+        for c in "one ".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.current_word_idx, 1);
+        assert!(tracker.tokens[3].is_wrong);
+        assert_eq!(tracker.total_errors, 1);
+    }
+
+    #[test]
+    fn test_enter_is_ignored_in_text_without_line_breaks() {
+        let mut tracker = Tracker::new("hello world".to_string(), Mode::with_words(2));
+        for c in "hello".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert!(tracker.type_char('\n').is_err());
+        assert_eq!(tracker.current_pos, 5);
+        tracker.type_char(' ').unwrap();
+        assert_eq!(tracker.words[0].error_count, 0);
+
+        // mid word it doesn't space jump either
+        for c in "wo".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert!(tracker.type_char('\n').is_err());
+        assert_eq!(tracker.current_pos, 8);
+        assert_eq!(tracker.total_errors, 0);
+        assert!(tracker.space_jump_stack.is_empty());
+    }
+
+    #[test]
+    fn test_enter_space_jumps_to_next_line() {
+        let mut tracker = Tracker::new("one two\n\nthree".to_string(), Mode::with_words(3));
+        for c in "one t".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        tracker.type_char('\n').unwrap();
+        assert_eq!(tracker.current_pos, 9);
+        assert_eq!(tracker.current_word_idx, 2);
+        assert_eq!(tracker.current_target_char(), Some('t'));
+        assert!(tracker.typed_text.ends_with("t\n\n\n\n"));
+
+        tracker.backspace().unwrap();
+        assert_eq!(tracker.current_pos, 5);
+        assert_eq!(tracker.current_word_idx, 1);
+    }
+
+    #[test]
+    fn test_backspace_blocked_after_correct_paragraph() {
+        let mut tracker = Tracker::new("one\n\ntwo".to_string(), Mode::with_words(2));
+        for c in "one\n\n".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        tracker.backspace().unwrap();
+        assert_eq!(tracker.current_pos, 5);
+        assert_eq!(tracker.current_word_idx, 1);
+    }
+
+    #[test]
+    fn test_space_jump_after_extra_tokens_lands_on_next_word() {
+        let mut tracker = Tracker::new("ab cd ef".to_string(), Mode::with_words(3));
+        for c in "abx cd".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        tracker.backspace().unwrap();
+        tracker.type_char(' ').unwrap();
+        assert_eq!(tracker.current_word_idx, 2);
+        assert_eq!(tracker.current_target_char(), Some('e'));
+        assert_eq!(tracker.current_pos, 7);
+    }
+
+    #[test]
+    fn test_tokens_keep_newline_and_indent_kinds() {
+        let tracker = Tracker::new("if x {\n    y\n}".to_string(), Mode::Code);
+        let kinds: Vec<TokenKind> = tracker.tokens.iter().map(|t| t.kind).collect();
+        assert_eq!(kinds[5], TokenKind::Char);
+        assert_eq!(kinds[6], TokenKind::Newline);
+        assert!(kinds[7..11].iter().all(|&k| k == TokenKind::Indent));
+        assert_eq!(kinds[11], TokenKind::Char);
+        assert_eq!(kinds[12], TokenKind::Newline);
+        assert_eq!(kinds[13], TokenKind::Char);
+    }
+
+    #[test]
+    fn test_code_mode_skips_indentation_after_newline() {
+        let mut tracker = Tracker::new("a {\n    b\n}".to_string(), Mode::Code);
+        for c in "a {\n".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.current_target_char(), Some('b'));
+        assert_eq!(tracker.current_pos, 8);
+        assert_eq!(tracker.current_word_idx, 2);
+        assert_eq!(tracker.total_errors, 0);
+        // indentation is not something the user typed
+        assert_eq!(tracker.correct_chars_count(), 4);
+        tracker.update_metrics();
+        assert_eq!(tracker.accuracy(), 1.0);
+
+        for c in "b\n}".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert!(tracker.check_completion());
+    }
+
+    #[test]
+    fn test_code_mode_backspace_reverts_indentation_with_newline() {
+        let mut tracker = Tracker::new("ab\n  c".to_string(), Mode::Code);
+        for c in "ax\n".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.current_pos, 5);
+
+        // the word is wrong so we can go back, indentation goes away with the line break
+        tracker.backspace().unwrap();
+        assert_eq!(tracker.current_pos, 2);
+        assert_eq!(tracker.current_word_idx, 0);
+        assert_eq!(tracker.typed_text, "ax");
+        assert!(tracker.tokens[3..5].iter().all(|t| t.typed.is_none()));
+    }
+
+    #[test]
+    fn test_code_mode_backspace_blocked_after_correct_line() {
+        let mut tracker = Tracker::new("ab\n  c".to_string(), Mode::Code);
+        for c in "ab\n".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        tracker.backspace().unwrap();
+        assert_eq!(tracker.current_pos, 5);
+        assert_eq!(tracker.current_target_char(), Some('c'));
+        assert_eq!(tracker.typed_text, "ab\n  ");
+    }
+
+    #[test]
+    fn test_auto_close_brackets() {
+        let text = "f(x) {\n}".to_string();
+        let options = TrackerOptions {
+            auto_close_brackets: true,
+            ..Default::default()
+        };
+        let mut tracker = Tracker::new(text, Mode::Code).with_options(options);
+        for c in "f(x".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        // `)` was typed for us
+        assert_eq!(tracker.current_target_char(), Some(' '));
+
+        for c in " {\n".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert!(tracker.check_completion());
+        assert_eq!(tracker.total_errors, 0);
+
+        // a wrong opening bracket is never auto closed
+        let mut tracker = Tracker::new("f(x)".to_string(), Mode::Code).with_options(options);
+        for c in "f[x".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.current_target_char(), Some(')'));
+    }
+
+    #[test]
+    fn test_auto_closed_bracket_is_reverted_on_backspace() {
+        let options = TrackerOptions {
+            auto_close_brackets: true,
+            ..Default::default()
+        };
+        let mut tracker = Tracker::new("f() x".to_string(), Mode::Code).with_options(options);
+        for c in "f(".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.current_pos, 3);
+
+        tracker.backspace().unwrap();
+        assert_eq!(tracker.current_pos, 1);
+        assert_eq!(tracker.typed_text, "f");
+        assert_eq!(tracker.correct_chars_count(), 1);
+    }
+
+    #[test]
+    fn test_reset_keeps_options() {
+        let options = TrackerOptions {
+            auto_close_brackets: true,
+            ..Default::default()
+        };
+        let mut tracker = Tracker::new("()".to_string(), Mode::Code).with_options(options);
+        tracker.reset("[]".to_string(), Mode::Code);
+        assert_eq!(tracker.options, options);
+    }
+
+    #[test]
+    fn test_stop_on_letter_rejects_wrong_keys() {
+        let options = TrackerOptions {
+            error_policy: ErrorPolicy::StopOnLetter,
+            ..Default::default()
+        };
+        let mut tracker =
+            Tracker::new("ab cd".to_string(), Mode::with_words(2)).with_options(options);
+        tracker.type_char('a').unwrap();
+        tracker.type_char('x').unwrap();
+        assert_eq!(tracker.current_pos, 1);
+        assert_eq!(tracker.typed_text, "a");
+
+        // no space jumps either
+        tracker.type_char(' ').unwrap();
+        assert_eq!(tracker.current_pos, 1);
+
+        for c in "b cd".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert!(tracker.check_completion());
+        assert_eq!(tracker.total_errors, 0);
+        assert!((tracker.calculate_accuracy() - 5.0 / 7.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_stop_on_word_requires_correct_word() {
+        let options = TrackerOptions {
+            error_policy: ErrorPolicy::StopOnWord,
+            ..Default::default()
+        };
+        let mut tracker =
+            Tracker::new("ab cd".to_string(), Mode::with_words(2)).with_options(options);
+        tracker.type_char('a').unwrap();
+        tracker.type_char('x').unwrap();
+        assert_eq!(tracker.current_pos, 2);
+
+        // the wrong word can't be submitted
+        tracker.type_char(' ').unwrap();
+        assert_eq!(tracker.current_pos, 2);
+        assert_eq!(tracker.current_word_idx, 0);
+
+        tracker.backspace().unwrap();
+        for c in "b c".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.current_word_idx, 1);
+
+        // neither can the last word of the test
+        tracker.type_char('x').unwrap();
+        assert!(!tracker.check_completion());
+        assert_eq!(tracker.current_pos, 4);
+        tracker.type_char('d').unwrap();
+        assert!(tracker.check_completion());
+    }
+
+    #[test]
+    fn test_master_fails_on_first_mistake() {
+        let options = TrackerOptions {
+            difficulty: Difficulty::Master,
+            ..Default::default()
+        };
+        let mut tracker =
+            Tracker::new("ab cd".to_string(), Mode::with_words(2)).with_options(options);
+        tracker.type_char('a').unwrap();
+        assert!(!tracker.is_failed());
+
+        tracker.type_char('x').unwrap();
+        assert_eq!(tracker.status, TypingStatus::Failed);
+        assert_eq!(tracker.failure, Some(FailureReason::Mistake));
+        assert!(tracker.is_complete());
+        assert!(tracker.type_char('b').is_err());
+    }
+
+    #[test]
+    fn test_expert_fails_on_submitted_word_with_errors() {
+        let options = TrackerOptions {
+            difficulty: Difficulty::Expert,
+            ..Default::default()
+        };
+        let mut tracker =
+            Tracker::new("ab cd ef".to_string(), Mode::with_words(3)).with_options(options);
+
+        // corrected mistakes are fine
+        for c in "ax".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        tracker.backspace().unwrap();
+        for c in "b c".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert!(!tracker.is_failed());
+
+        // submitting an incomplete word is not
+        tracker.type_char(' ').unwrap();
+        assert!(tracker.is_failed());
+        assert_eq!(tracker.failure, Some(FailureReason::WordWithErrors));
+    }
+
+    #[test]
+    fn test_threshold_failure() {
+        let options = TrackerOptions {
+            min_accuracy: 90,
+            ..Default::default()
+        };
+        let mut tracker =
+            Tracker::new("ab cd".to_string(), Mode::with_words(2)).with_options(options);
+        for c in "xb".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        tracker.update_metrics();
+
+        // grace period
+        tracker.check_threshold_failure();
+        assert!(!tracker.is_failed());
+
+        tracker.start_time = Some(Instant::now() - Duration::from_secs(5));
+        tracker.check_threshold_failure();
+        assert_eq!(tracker.failure, Some(FailureReason::LowAccuracy(90)));
+        assert!(!tracker.check_completion());
+    }
+
+    #[test]
+    fn test_pace_position() {
+        let mut tracker = Tracker::new("a".repeat(100), Mode::with_time(30));
+        assert_eq!(tracker.pace_position(60.0), 0);
+
+        tracker.start_typing();
+        // 60 wpm is 5 chars per second
+        tracker.start_time = Some(Instant::now() - Duration::from_secs(4));
+        assert_eq!(tracker.pace_position(60.0), 20);
+        assert_eq!(tracker.pace_position(600.0), 100);
+    }
+
+    #[test]
+    fn test_text_position_skips_extra_tokens() {
+        let mut tracker = Tracker::new("ab cd".to_string(), Mode::with_words(2));
+        for c in "abx".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        // `x` is an extra token at the word boundary
+        assert_eq!(tracker.current_pos, 3);
+        assert_eq!(tracker.text_position(), 2);
+        assert_eq!(tracker.token_index_of(2), 3);
+        assert_eq!(tracker.token_index_of(1), 1);
+    }
+
+    #[test]
+    fn test_extend_last_word() {
+        let mut tracker = Tracker::new("abcd".to_string(), Mode::with_time(30));
+        for c in "abc".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.remaining_chars(), 1);
+
+        tracker.extend_last_word("efg");
+        assert_eq!(tracker.text, "abcdefg");
+        assert_eq!(tracker.words.len(), 1);
+        assert_eq!(tracker.words[0].target, "abcdefg");
+        assert_eq!(tracker.remaining_chars(), 4);
+
+        for c in "defg".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.total_errors, 0);
+        assert_eq!(tracker.remaining_chars(), 0);
+    }
+
+    #[test]
+    fn test_extend_text() {
+        let mut tracker = Tracker::new("ab cd".to_string(), Mode::with_time(30));
+        // `x` is an extra token at the word boundary
+        for c in "abx c".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.remaining_words(), 1);
+
+        tracker.extend_text("ef gh");
+        assert_eq!(tracker.text, "ab cd ef gh");
+        assert_eq!(tracker.words.len(), 4);
+        assert_eq!(tracker.remaining_words(), 3);
+        assert_eq!(tracker.word_boundaries, vec![0, 4, 7, 10]);
+
+        // the test goes on into the new words
+        for c in "d ef".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.current_word_idx, 2);
+        assert_eq!(tracker.tokens[tracker.current_pos - 1].target, 'f');
+        assert!(!tracker.should_complete());
+    }
+
+    #[test]
+    fn test_missed_words() {
+        let mut tracker = Tracker::new("ab cd ef ab".to_string(), Mode::with_words(4));
+        // `ab` is typed wrong twice, `cd` is space jumped
+        for c in "ax c ef ax".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.missed_words(), vec!["ab", "cd"]);
+
+        let mut tracker = Tracker::new("ab cd".to_string(), Mode::with_words(2));
+        for c in "ax".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        // corrected mistakes are not missed words
+        tracker.backspace().unwrap();
+        tracker.type_char('b').unwrap();
+        assert!(tracker.missed_words().is_empty());
+    }
+
+    #[test]
+    fn test_char_stats() {
+        let mut tracker = Tracker::new("ab cd ef".to_string(), Mode::with_words(3));
+        for c in "ax".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        tracker.backspace().unwrap();
+        // `z` is an extra character past the end of `ab`
+        for c in "bz".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        tracker.backspace().unwrap();
+        // `d` is skipped
+        for c in " c ".chars() {
+            tracker.type_char(c).unwrap();
+        }
+
+        let expected = CharStats {
+            correct: 4,
+            incorrect: 2,
+            corrected: 2,
+            extra: 1,
+            missed: 1,
+        };
+        assert_eq!(tracker.char_stats, expected);
+        assert_eq!(expected.to_string(), "4/2/1/1");
+
+        let summary = tracker.summary();
+        assert_eq!(summary.keystroke_accuracy, 4.0 / 7.0);
+        // only the skipped `d` is left wrong in the final text
+        assert!(tracker.calculate_accuracy() > summary.keystroke_accuracy);
+
+        // undoing the jump brings the skipped character back
+        tracker.backspace().unwrap();
+        assert_eq!(tracker.char_stats.missed, 0);
+        assert_eq!(tracker.char_stats.corrected, 3);
+    }
+
+    #[test]
+    fn test_second_samples() {
+        let mut tracker = Tracker::new("ab cd".to_string(), Mode::with_words(2));
+        tracker.start_typing();
+        tracker.start_time = Some(Instant::now() - Duration::from_secs(2));
+        for c in "ax".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        tracker.try_metrics_update();
+        // a second hasn't gone by since the last sample
+        tracker.try_metrics_update();
+
+        let seconds = tracker.wpm_snapshots.seconds();
+        assert_eq!(seconds.len(), 1);
+        assert_eq!(seconds[0].errors, 1);
+        // two keystrokes in two seconds
+        assert!((seconds[0].raw_wpm - 12.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_word_bursts() {
+        let mut tracker = Tracker::new("hello world".to_string(), Mode::with_words(2));
+        for c in "hello ".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        let start = tracker.start_time.unwrap();
+        tracker.words[0].start_time = Some(start);
+        tracker.words[0].end_time = Some(start + Duration::from_secs(1));
+
+        // `world` isn't completed yet
+        let bursts = tracker.word_bursts();
+        assert_eq!(bursts.len(), 1);
+        assert_eq!(bursts[0].at_secs, 1.0);
+        assert_eq!(bursts[0].wpm, 60.0);
+    }
+
+    fn tracker_with_keystrokes(intervals: &[u64]) -> Tracker {
+        let mut tracker = Tracker::new("ab cd".to_string(), Mode::with_words(MIN_VALID_WORDS));
+        let mut at_ms = 0;
+        tracker.keystrokes.push(Keystroke {
+            at_ms,
+            kind: KeystrokeKind::Char('a'),
+        });
+        for gap in intervals {
+            at_ms += gap;
+            tracker.keystrokes.push(Keystroke {
+                at_ms,
+                kind: KeystrokeKind::Char('a'),
+            });
+        }
+        let start = Instant::now();
+        tracker.start_time = Some(start);
+        tracker.end_time = Some(start + Duration::from_millis(at_ms));
+        tracker
+    }
+
+    #[test]
+    fn test_validity() {
+        let human: Vec<u64> = (0..30).map(|i| 80 + (i * 37) % 90).collect();
+        assert!(tracker_with_keystrokes(&human).validity().is_valid());
+
+        let mut idle = human.clone();
+        idle[10] = IDLE_GAP_MS + 1;
+        let validity = tracker_with_keystrokes(&idle).validity();
+        assert_eq!(validity.issues, vec![ValidityIssue::Idle]);
+
+        let metronome = vec![100; 30];
+        let validity = tracker_with_keystrokes(&metronome).validity();
+        assert_eq!(validity.issues, vec![ValidityIssue::UniformTiming]);
+
+        let mut pasted = human.clone();
+        pasted.splice(5..5, vec![0; PASTE_BURST_LEN]);
+        let validity = tracker_with_keystrokes(&pasted).validity();
+        assert_eq!(validity.issues, vec![ValidityIssue::Pasted]);
+        assert_eq!(validity.to_string(), "pasted input");
+
+        // the whole text typed in a tenth of a second
+        let mut tracker = Tracker::new("ab cd".to_string(), Mode::with_words(2));
+        for c in "ab cd".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        let start = Instant::now();
+        tracker.start_time = Some(start);
+        tracker.end_time = Some(start + Duration::from_millis(100));
+        assert!(tracker.validity().issues.contains(&ValidityIssue::TooFast));
+
+        // texts that end once typed through need enough words, whatever the mode
+        let short = "one two three";
+        let long = ["word"; MIN_VALID_WORDS].join(" ");
+        for mode in [
+            Mode::Quote(QuoteLength::Short),
+            Mode::Code,
+            Mode::with_words(3),
+        ] {
+            let validity = Tracker::new(short.to_string(), mode).validity();
+            assert!(
+                validity.issues.contains(&ValidityIssue::TooShort),
+                "{mode:?}"
+            );
+        }
+        for mode in [Mode::Quote(QuoteLength::Short), Mode::Code] {
+            let validity = Tracker::new(long.clone(), mode).validity();
+            assert!(
+                !validity.issues.contains(&ValidityIssue::TooShort),
+                "{mode:?}"
+            );
+        }
+        let validity = Tracker::new(String::new(), Mode::with_time(10)).validity();
+        assert!(validity.issues.contains(&ValidityIssue::TooShort));
+    }
+
+    #[test]
+    fn test_keystrokes_are_recorded() {
+        let mut tracker = Tracker::new("ab cd".to_string(), Mode::with_words(2));
+        // nothing happens on illegal keystrokes, nothing to record
+        assert!(tracker.type_char(' ').is_err());
+        for c in "ax".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        tracker.backspace().unwrap();
+
+        let kinds: Vec<KeystrokeKind> = tracker.keystrokes.iter().map(|k| k.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                KeystrokeKind::Char('a'),
+                KeystrokeKind::Char('x'),
+                KeystrokeKind::Backspace
+            ]
+        );
+        assert!(
+            tracker
+                .keystrokes
+                .windows(2)
+                .all(|pair| pair[0].at_ms <= pair[1].at_ms)
+        );
+    }
+
+    #[test]
+    fn test_grapheme_tokens() {
+        // `e` + combining acute accent is a single token that takes two keystrokes
+        let mut tracker = Tracker::new("ae\u{301} 日本".to_string(), Mode::with_words(2));
+        assert_eq!(tracker.tokens.len(), 5);
+        assert_eq!(tracker.tokens[3].target.width(), 2);
+
+        for c in "ae".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.current_pos, 1);
+        assert!(tracker.tokens[1].typed.is_none());
+
+        // taking back half a grapheme keeps the cursor where it is
+        tracker.backspace().unwrap();
+        assert_eq!(tracker.current_pos, 1);
+        for c in "e\u{301}".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert_eq!(tracker.current_pos, 2);
+        assert!(tracker.tokens[1].is_typed_correctly());
+        assert_eq!(tracker.typed_text, "ae\u{301}");
+
+        tracker.backspace().unwrap();
+        assert_eq!(tracker.current_pos, 1);
+        assert_eq!(tracker.typed_text, "a");
+    }
+
+    #[test]
+    fn test_normalized_input() {
+        let text = "caf\u{e9}".to_string();
+        let mut tracker = Tracker::new(text.clone(), Mode::with_words(1));
+        for c in "cafe".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert!(tracker.tokens[3].is_wrong);
+
+        let options = TrackerOptions {
+            normalize: true,
+            ..Default::default()
+        };
+        let mut tracker = Tracker::new(text, Mode::with_words(1)).with_options(options);
+        for c in "cafe\u{301}".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert!(tracker.tokens.iter().all(Token::is_typed_correctly));
+        assert_eq!(tracker.correct_chars_count(), 4);
+        assert!(tracker.check_completion());
+    }
+
+    #[test]
+    fn test_lazy_mode() {
+        let options = TrackerOptions {
+            lazy: true,
+            ..Default::default()
+        };
+        let mut tracker = Tracker::new("año".to_string(), Mode::with_words(1))
+            .with_options(options)
+            .with_folding(Folding::for_language("spanish"));
+        for c in "ano".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert!(tracker.tokens.iter().all(Token::is_typed_correctly));
+
+        // typing the accent is still fine, the folding survives a reset
+        tracker.reset("año".to_string(), Mode::with_words(1));
+        for c in "año".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert!(tracker.tokens.iter().all(Token::is_typed_correctly));
+
+        tracker.options.lazy = false;
+        tracker.reset("año".to_string(), Mode::with_words(1));
+        for c in "ano".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        assert!(tracker.tokens[1].is_wrong);
+    }
 }
@@ -0,0 +1,236 @@
+use crate::{
+    key_stats::{KeyStat, KeyStats, NgramStats},
+    theme::Theme,
+    tui::helpers::center_lines_vertically,
+};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use std::collections::BTreeMap;
+
+const KEYBOARD_ROWS: [&str; 4] = ["1234567890-=", "qwertyuiop[]", "asdfghjkl;'", "zxcvbnm,./"];
+/// Symbols typed with <Shift> and the key they are on
+const SHIFTED_KEYS: [(char, char); 21] = [
+    ('!', '1'),
+    ('@', '2'),
+    ('#', '3'),
+    ('$', '4'),
+    ('%', '5'),
+    ('^', '6'),
+    ('&', '7'),
+    ('*', '8'),
+    ('(', '9'),
+    (')', '0'),
+    ('_', '-'),
+    ('+', '='),
+    ('{', '['),
+    ('}', ']'),
+    (':', ';'),
+    ('"', '\''),
+    ('<', ','),
+    ('>', '.'),
+    ('?', '/'),
+    ('|', '\\'),
+    ('~', '`'),
+];
+/// Miss rate at which a key is fully coloured as an error
+const MAX_HEAT_MISS_RATE: f64 = 0.2;
+const SUMMARY_KEY_COUNT: usize = 5;
+
+/// Draws an on-screen keyboard with every key coloured by how often it was missed, followed by
+/// the most missed and the slowest keys and sequences. Sequences typed fewer than
+/// `min_occurrences` times are left out
+pub fn render(
+    frame: &mut Frame,
+    theme: &Theme,
+    stats: &KeyStats,
+    ngrams: &NgramStats,
+    min_occurrences: u32,
+    area: Rect,
+) {
+    if stats.is_empty() {
+        let message = Paragraph::new("No key statistics yet")
+            .style(Style::default().fg(theme.fg()).add_modifier(Modifier::DIM))
+            .alignment(Alignment::Center);
+        let lines_area = Rect {
+            y: area.y + area.height / 2,
+            height: 1.min(area.height),
+            ..area
+        };
+        frame.render_widget(message, lines_area);
+        return;
+    }
+
+    let keys = keyboard_stats(stats);
+    let mut lines = keyboard_lines(theme, &keys);
+    lines.push(Line::from(""));
+    lines.push(most_missed_line(theme, stats));
+    lines.push(slowest_line(theme, stats));
+    lines.push(Line::from(""));
+    lines.push(most_missed_ngrams_line(theme, ngrams, min_occurrences));
+    lines.push(slowest_ngrams_line(theme, ngrams, min_occurrences));
+
+    let lines = center_lines_vertically(lines, area.height);
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center), area);
+}
+
+/// Merges the statistics of the characters typed on the same physical key
+fn keyboard_stats(stats: &KeyStats) -> BTreeMap<char, KeyStat> {
+    let mut keys: BTreeMap<char, KeyStat> = BTreeMap::new();
+    for (c, stat) in stats.iter() {
+        let key = SHIFTED_KEYS
+            .iter()
+            .find(|(shifted, _)| *shifted == c)
+            .map_or(c.to_ascii_lowercase(), |(_, key)| *key);
+        keys.entry(key).or_default().merge(stat);
+    }
+    keys
+}
+
+fn keyboard_lines(theme: &Theme, keys: &BTreeMap<char, KeyStat>) -> Vec<Line<'static>> {
+    let row_width = |row: &str, indent: usize| indent + row.chars().count() * 4;
+    let width = KEYBOARD_ROWS
+        .iter()
+        .enumerate()
+        .map(|(idx, row)| row_width(row, idx * 2))
+        .max()
+        .unwrap_or(0);
+
+    let mut lines: Vec<Line<'static>> = KEYBOARD_ROWS
+        .iter()
+        .enumerate()
+        .map(|(idx, row)| {
+            // rows are staggered like on a real keyboard, trailing space keeps them aligned
+            let indent = idx * 2;
+            let mut spans = vec![Span::raw(" ".repeat(indent))];
+            for c in row.chars() {
+                spans.push(key_span(theme, keys.get(&c), format!(" {c} ")));
+                spans.push(Span::raw(" "));
+            }
+            spans.push(Span::raw(" ".repeat(width - row_width(row, indent))));
+            Line::from(spans)
+        })
+        .collect();
+
+    lines.push(Line::from(vec![key_span(
+        theme,
+        keys.get(&' '),
+        format!("{:^24}", "space"),
+    )]));
+    lines
+}
+
+fn key_span(theme: &Theme, stat: Option<&KeyStat>, label: String) -> Span<'static> {
+    let style = match stat {
+        Some(stat) if stat.attempts > 0 => Style::default()
+            .fg(theme.bg())
+            .bg(heat_color(theme, stat.miss_rate()))
+            .add_modifier(Modifier::BOLD),
+        _ => Style::default()
+            .fg(theme.muted())
+            .bg(theme.selection_bg())
+            .add_modifier(Modifier::DIM),
+    };
+    Span::styled(label, style)
+}
+
+/// Blends the success and error colours of the theme by the given miss rate. Themes without
+/// true colours get a warning colour in between instead
+fn heat_color(theme: &Theme, miss_rate: f64) -> Color {
+    let heat = (miss_rate / MAX_HEAT_MISS_RATE).clamp(0.0, 1.0);
+    match (theme.success(), theme.error()) {
+        (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) => {
+            let blend = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * heat) as u8;
+            Color::Rgb(blend(r1, r2), blend(g1, g2), blend(b1, b2))
+        }
+        (success, error) => {
+            if heat == 0.0 {
+                success
+            } else if heat < 0.5 {
+                theme.warning()
+            } else {
+                error
+            }
+        }
+    }
+}
+
+fn display_key(c: char) -> String {
+    match c {
+        ' ' => "space".to_string(),
+        '\n' => "enter".to_string(),
+        c => c.to_string(),
+    }
+}
+
+fn most_missed_line(theme: &Theme, stats: &KeyStats) -> Line<'static> {
+    let label_style = Style::default().fg(theme.fg()).add_modifier(Modifier::DIM);
+    let mut spans = vec![Span::styled("most missed  ", label_style)];
+    let most_missed = stats.most_missed(SUMMARY_KEY_COUNT);
+    if most_missed.is_empty() {
+        spans.push(Span::styled("none", Style::default().fg(theme.success())));
+    }
+    for (c, stat) in most_missed {
+        spans.push(Span::styled(
+            format!("{} {:.0}%", display_key(c), stat.miss_rate() * 100.0),
+            Style::default().fg(theme.error()),
+        ));
+        if let Some(substitution) = stat.top_substitution() {
+            spans.push(Span::styled(
+                format!(" →{}", display_key(substitution)),
+                label_style,
+            ));
+        }
+        spans.push(Span::raw("  "));
+    }
+    Line::from(spans)
+}
+
+fn slowest_line(theme: &Theme, stats: &KeyStats) -> Line<'static> {
+    let label_style = Style::default().fg(theme.fg()).add_modifier(Modifier::DIM);
+    let mut spans = vec![Span::styled("slowest  ", label_style)];
+    for (c, stat) in stats.slowest(SUMMARY_KEY_COUNT) {
+        let latency = stat.average_latency_ms().unwrap_or(0.0);
+        spans.push(Span::styled(
+            format!("{} {latency:.0}ms  ", display_key(c)),
+            Style::default().fg(theme.warning()),
+        ));
+    }
+    Line::from(spans)
+}
+
+fn most_missed_ngrams_line(
+    theme: &Theme,
+    ngrams: &NgramStats,
+    min_occurrences: u32,
+) -> Line<'static> {
+    let label_style = Style::default().fg(theme.fg()).add_modifier(Modifier::DIM);
+    let mut spans = vec![Span::styled("most missed n-grams  ", label_style)];
+    let most_missed = ngrams.most_missed(NgramStats::SUMMARY_COUNT, min_occurrences);
+    if most_missed.is_empty() {
+        spans.push(Span::styled("none", Style::default().fg(theme.success())));
+    }
+    for (ngram, stat) in most_missed {
+        spans.push(Span::styled(
+            format!("{ngram} {:.0}%  ", stat.miss_rate() * 100.0),
+            Style::default().fg(theme.error()),
+        ));
+    }
+    Line::from(spans)
+}
+
+fn slowest_ngrams_line(theme: &Theme, ngrams: &NgramStats, min_occurrences: u32) -> Line<'static> {
+    let label_style = Style::default().fg(theme.fg()).add_modifier(Modifier::DIM);
+    let mut spans = vec![Span::styled("slowest n-grams  ", label_style)];
+    for (ngram, stat) in ngrams.slowest(NgramStats::SUMMARY_COUNT, min_occurrences) {
+        spans.push(Span::styled(
+            format!("{ngram} {:.0}ms  ", stat.average_latency_ms()),
+            Style::default().fg(theme.warning()),
+        ));
+    }
+    Line::from(spans)
+}
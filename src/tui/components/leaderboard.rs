@@ -1,8 +1,10 @@
 use crate::{
     app::App,
     db::{LeaderboardColumn, SortOrder},
+    key_stats::NgramStats,
     log_info,
     theme::Theme,
+    tui::components::heatmap,
 };
 use ratatui::{
     Frame,
@@ -55,11 +57,16 @@ impl LeaderboardOverlay {
         let content_area = chunks[0];
         let bottom_bar_area = chunks[1];
 
+        let title = if leaderboard.key_stats().is_some() {
+            " All-time Key Stats "
+        } else {
+            " Leaderboard "
+        };
         let block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .border_style(Style::default().fg(theme.fg()).add_modifier(Modifier::DIM))
-            .title(" Leaderboard ")
+            .title(title)
             .title_alignment(Alignment::Center)
             .title_style(Style::default().fg(theme.fg()).bold())
             .padding(Padding::symmetric(1, 1))
@@ -68,6 +75,27 @@ impl LeaderboardOverlay {
         let inner_area = block.inner(content_area);
         frame.render_widget(block, content_area);
 
+        if let (Some(stats), Some(ngrams)) = (leaderboard.key_stats(), leaderboard.ngram_stats()) {
+            heatmap::render(
+                frame,
+                theme,
+                stats,
+                ngrams,
+                NgramStats::ALL_TIME_MIN_OCCURRENCES,
+                inner_area,
+            );
+            let hint = Paragraph::new("<p> practice  |  <h> back to results  ")
+                .style(
+                    Style::default()
+                        .fg(theme.fg())
+                        .bg(theme.bg())
+                        .add_modifier(Modifier::DIM),
+                )
+                .alignment(Alignment::Right);
+            frame.render_widget(hint, bottom_bar_area);
+            return;
+        }
+
         Self::render_table(frame, app, theme, inner_area);
         Self::render_bottom_bar(frame, app, theme, bottom_bar_area);
     }
@@ -136,7 +164,7 @@ impl LeaderboardOverlay {
         let rows: Vec<Row> = data
             .iter()
             .map(|result| {
-                let mode_display = format!("{}({})", result.mode_kind, result.mode_value);
+                let mode_display = result.mode_display();
                 let date_format = if width >= 30 {
                     "%d %b %Y %H:%M"
                 } else {
@@ -173,9 +201,13 @@ impl LeaderboardOverlay {
 
                 cells.push(date_display);
 
-                Row::new(cells)
-                    .style(Style::default().fg(theme.fg()))
-                    .height(1)
+                // invalid results are only listed on demand, they stand out when they are
+                let fg = if result.valid {
+                    theme.fg()
+                } else {
+                    theme.warning()
+                };
+                Row::new(cells).style(Style::default().fg(fg)).height(1)
             })
             .collect();
 
@@ -348,11 +380,21 @@ impl LeaderboardOverlay {
             SortOrder::Ascending => "↑",
         };
 
+        let invalid_hint = if leaderboard.shows_invalid() {
+            "<i> hide invalid"
+        } else {
+            "<i> show invalid"
+        };
         let status_text = if total_count == 0 {
-            "No results".to_string()
+            format!("{invalid_hint}  |  No results  ")
         } else {
+            let replay_hint = leaderboard
+                .selected()
+                .and_then(|result| result.id)
+                .map(|id| format!("<Enter> replay #{id}  |  "))
+                .unwrap_or_default();
             format!(
-                "{current_selection}/{total_count} results  |  Sort: {sort_name} {sort_indicator}  "
+                "{replay_hint}<h> key stats  |  {invalid_hint}  |  {current_selection}/{total_count} results  |  Sort: {sort_name} {sort_indicator}  "
             )
         };
 
@@ -1,5 +1,6 @@
 pub mod command_bar;
 pub mod footer;
+pub mod heatmap;
 pub mod leaderboard;
 pub mod modal_dialog;
 pub mod mode_bar;
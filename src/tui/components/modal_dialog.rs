@@ -110,6 +110,9 @@ impl ModalDialog {
             ModalContext::CustomTime => (3, " second(s)"), // 300 is the max custom time
             ModalContext::CustomWordCount => (4, " word(s)"), // 5000 is the max custom word count
             ModalContext::CustomLineCount => (2, " line(s)"), // 10 is the max custom line
+            ModalContext::MinAccuracy => (3, " %"),
+            ModalContext::MinWpm => (3, " wpm"), // 350 is the max minimum wpm
+            ModalContext::PaceCaretWpm => (3, " wpm"), // 350 is the max pace caret wpm
             _ => unreachable!(),
         };
 
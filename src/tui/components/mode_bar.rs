@@ -1,4 +1,8 @@
-use crate::{app::App, config::Setting, theme::Theme};
+use crate::{
+    app::App,
+    config::{QuoteLength, Setting},
+    theme::Theme,
+};
 use ratatui::{
     layout::Alignment,
     style::{Modifier, Style},
@@ -6,7 +10,7 @@ use ratatui::{
     widgets::{Block, Padding, Paragraph},
 };
 
-const MODE_LINE_MIN_WIDTH: u16 = 80;
+const MODE_LINE_MIN_WIDTH: u16 = 98;
 
 pub fn create_mode_line<'a>(
     app: &App,
@@ -22,6 +26,30 @@ pub fn create_mode_line<'a>(
 
     let current_mode = app.config.current_mode();
 
+    // racing a ghost, the bar keeps the score instead
+    if let Some(ghost) = &app.ghost {
+        let ahead = ghost.chars_ahead(&app.tracker);
+        let ahead_color = match ahead.signum() {
+            1 => theme.success(),
+            -1 => theme.error(),
+            _ => theme.fg(),
+        };
+        let spans = vec![
+            Span::styled(format!("ghost {} wpm ", ghost.wpm), fg_style),
+            Span::styled("| ", fg_style),
+            Span::styled(
+                format!("{ahead:+} chars ahead"),
+                Style::default()
+                    .fg(ahead_color)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ];
+        return Paragraph::new(Line::from(spans))
+            .style(Style::default())
+            .alignment(Alignment::Center)
+            .block(Block::default().padding(mode_line_padding(container_height)));
+    }
+
     // show compact menu if not enough width
     if container_width < MODE_LINE_MIN_WIDTH {
         let compact_spans = vec![
@@ -79,9 +107,69 @@ pub fn create_mode_line<'a>(
     };
     spans.push(Span::styled("A words ", word_mode_style));
 
+    // quote
+    let quote_mode_style = if app.config.current_mode().is_quote_mode() {
+        highlight_style
+    } else {
+        fg_style
+    };
+    spans.push(Span::styled("\" quote ", quote_mode_style));
+
+    // zen
+    let zen_mode_style = if app.config.current_mode().is_zen_mode() {
+        highlight_style
+    } else {
+        fg_style
+    };
+    spans.push(Span::styled("~ zen ", zen_mode_style));
+
+    // code
+    let code_mode_style = if app.config.current_mode().is_code_mode() {
+        highlight_style
+    } else {
+        fg_style
+    };
+    spans.push(Span::styled("{ code ", code_mode_style));
+
     // separator
     spans.push(Span::styled("| ", fg_style));
 
+    if current_mode.is_zen_mode() {
+        spans.push(Span::styled("<C-d> to finish ", fg_style));
+        return Paragraph::new(Line::from(spans))
+            .style(Style::default())
+            .alignment(Alignment::Center)
+            .block(Block::default().padding(mode_line_padding(container_height)));
+    }
+
+    if current_mode.is_code_mode() {
+        let auto_close_style = if app.config.is_enabled(Setting::AutoCloseBrackets) {
+            highlight_style
+        } else {
+            fg_style
+        };
+        spans.push(Span::styled("() auto-close ", auto_close_style));
+        return Paragraph::new(Line::from(spans))
+            .style(Style::default())
+            .alignment(Alignment::Center)
+            .block(Block::default().padding(mode_line_padding(container_height)));
+    }
+
+    if let Some(current_length) = current_mode.quote_length() {
+        for &length in QuoteLength::ALL {
+            let length_style = if length == current_length {
+                highlight_style
+            } else {
+                fg_style
+            };
+            spans.push(Span::styled(format!("{} ", length.label()), length_style));
+        }
+        return Paragraph::new(Line::from(spans))
+            .style(Style::default())
+            .alignment(Alignment::Center)
+            .block(Block::default().padding(mode_line_padding(container_height)));
+    }
+
     // TODO: add custom values
     let is_time_mode = current_mode.is_time_mode();
     let values = if is_time_mode {
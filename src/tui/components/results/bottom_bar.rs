@@ -14,11 +14,14 @@ const VARIANTS: &[(&str, &str, ResultsVariant)] = &[
     ("Graph", "g", ResultsVariant::Graph),
     ("Neofetch", "n", ResultsVariant::Neofetch),
     ("Minimal", "m", ResultsVariant::Minimal),
+    ("Heatmap", "h", ResultsVariant::Heatmap),
 ];
 
 const ACTIONS: &[(&str, &str)] = &[
     ("New", "N"),
     ("Redo", "R"),
+    ("Drill Missed", "P"),
+    ("Drill N-grams", "p"),
     ("Menu", "esc"),
     ("Quit", "^c"),
     ("Randomize Theme", "^t"),
@@ -1,4 +1,4 @@
-use crate::{app::App, theme::Theme, tracker::Summary};
+use crate::{app::App, config::Mode, theme::Theme, tracker::Summary};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
@@ -19,24 +19,44 @@ pub fn render(frame: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
 }
 
 fn render_wpm_chart(frame: &mut Frame, summary: &Summary, theme: &Theme, area: Rect) {
-    let snapshots = &summary.snapshots;
-
-    let data: Vec<(f64, f64)> = if !snapshots.is_empty() {
-        snapshots
-            .iter()
-            .enumerate()
-            .map(|(i, &wpm)| (i as f64, wpm))
-            .collect()
-    } else {
-        vec![]
-    };
+    let seconds = summary.snapshots.seconds();
+    // the first sample is taken one second into the test
+    let at = |i: usize| (i + 1) as f64;
 
-    let max_time = if data.len() > 1 {
-        (data.len() - 1) as f64
-    } else {
-        1.0
-    };
-    let max_wpm = snapshots.max().max(10.0);
+    let wpm_data: Vec<(f64, f64)> = seconds
+        .iter()
+        .enumerate()
+        .map(|(i, sample)| (at(i), sample.wpm))
+        .collect();
+    let raw_data: Vec<(f64, f64)> = seconds
+        .iter()
+        .enumerate()
+        .map(|(i, sample)| (at(i), sample.raw_wpm))
+        .collect();
+    // mistakes sit on the raw line, at the second they were made
+    let error_data: Vec<(f64, f64)> = seconds
+        .iter()
+        .enumerate()
+        .filter(|(_, sample)| sample.errors > 0)
+        .map(|(i, sample)| (at(i), sample.raw_wpm))
+        .collect();
+    let burst_data: Vec<(f64, f64)> = summary
+        .word_bursts
+        .iter()
+        .map(|burst| (burst.at_secs, burst.wpm))
+        .collect();
+
+    let max_time = burst_data
+        .iter()
+        .map(|&(secs, _)| secs)
+        .fold(seconds.len() as f64, f64::max)
+        .max(1.0);
+    let max_wpm = wpm_data
+        .iter()
+        .chain(&raw_data)
+        .chain(&burst_data)
+        .map(|&(_, wpm)| wpm)
+        .fold(10.0, f64::max);
     let min_wpm = 0.0;
 
     let y_upper_bound = (max_wpm * 1.2).max(20.0);
@@ -44,19 +64,11 @@ fn render_wpm_chart(frame: &mut Frame, summary: &Summary, theme: &Theme, area: R
     let axis_line_style = Style::default().fg(theme.fg()).add_modifier(Modifier::DIM);
 
     // X-axis label
-    let x_labels = if max_time > 0.0 {
-        vec![
-            Span::styled("0s", axis_value_style),
-            Span::styled(format!("{}s", (max_time / 2.0).round()), axis_value_style),
-            Span::styled(format!("{}s", max_time.round()), axis_value_style),
-        ]
-    } else {
-        vec![
-            Span::styled("0s", axis_value_style),
-            Span::raw(""),
-            Span::styled("0s", axis_value_style),
-        ]
-    };
+    let x_labels = vec![
+        Span::styled("0s", axis_value_style),
+        Span::styled(format!("{}s", (max_time / 2.0).round()), axis_value_style),
+        Span::styled(format!("{}s", max_time.round()), axis_value_style),
+    ];
 
     // Y-axis labels
     let y_labels = vec![
@@ -65,14 +77,34 @@ fn render_wpm_chart(frame: &mut Frame, summary: &Summary, theme: &Theme, area: R
         Span::styled(format!("{y_upper_bound:.0}"), axis_value_style),
     ];
 
-    let dataset = Dataset::default()
-        .name("WPM")
-        .marker(symbols::Marker::Braille)
-        .graph_type(GraphType::Line)
-        .style(Style::default().fg(theme.accent()))
-        .data(&data);
+    let datasets = vec![
+        Dataset::default()
+            .name("Raw")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(theme.muted()))
+            .data(&raw_data),
+        Dataset::default()
+            .name("Burst")
+            .marker(symbols::Marker::Dot)
+            .graph_type(GraphType::Scatter)
+            .style(Style::default().fg(theme.success()))
+            .data(&burst_data),
+        Dataset::default()
+            .name("WPM")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(theme.accent()))
+            .data(&wpm_data),
+        Dataset::default()
+            .name("Errors")
+            .marker(symbols::Marker::Dot)
+            .graph_type(GraphType::Scatter)
+            .style(Style::default().fg(theme.error()))
+            .data(&error_data),
+    ];
 
-    let chart = Chart::new(vec![dataset])
+    let chart = Chart::new(datasets)
         .block(
             Block::bordered()
                 .border_style(axis_line_style)
@@ -93,7 +125,7 @@ fn render_wpm_chart(frame: &mut Frame, summary: &Summary, theme: &Theme, area: R
                 .title("Time")
                 .style(axis_line_style)
                 .labels(x_labels)
-                .bounds([0.0, max_time.max(1.0)]),
+                .bounds([0.0, max_time]),
         )
         .y_axis(
             Axis::default()
@@ -148,6 +180,13 @@ fn render_performance_section(
             Span::styled("Accuracy: ", label_style),
             Span::styled(format!("{:.0}%", summary.accuracy * 100.0), warning_style),
         ]),
+        Line::from(vec![
+            Span::styled("Key Accuracy: ", label_style),
+            Span::styled(
+                format!("{:.0}%", summary.keystroke_accuracy * 100.0),
+                warning_style,
+            ),
+        ]),
         Line::from(vec![
             Span::styled("Consistency: ", label_style),
             Span::styled(format!("{:.0}%", summary.consistency), value_style),
@@ -178,10 +217,13 @@ fn render_details_section(
     let error_style = Style::default().fg(theme.error());
     let success_style = Style::default().fg(theme.success());
 
-    let mode_info = if app.config.current_mode().is_time_mode() {
-        format!("Time ({}s)", app.config.current_mode().value())
-    } else {
-        format!("Words ({})", app.config.current_mode().value())
+    let mode_info = match app.tracker.mode {
+        Mode::Time(secs) => format!("Time ({secs}s)"),
+        Mode::Words(count) => format!("Words ({count})"),
+        Mode::Quote(length) => format!("Quote ({})", length.label()),
+        Mode::Zen => "Zen".to_string(),
+        Mode::Code => "Code".to_string(),
+        Mode::Practice(repetitions) => format!("Practice ({repetitions}x)"),
     };
 
     let elapsed_secs = summary.elapsed_time.as_secs_f64();
@@ -201,7 +243,7 @@ fn render_details_section(
         format!("{min_wpm:.0}-{max_wpm:.0}")
     };
 
-    let lines = vec![
+    let mut lines = vec![
         Line::from(vec![
             Span::styled("Mode: ", label_style),
             Span::styled(mode_info, value_style),
@@ -227,8 +269,12 @@ fn render_details_section(
             Span::styled(format!("{}", summary.total_errors), error_style),
         ]),
         Line::from(vec![
-            Span::styled("Backspaces: ", label_style),
-            Span::styled("0", value_style),
+            Span::styled("Corrected: ", label_style),
+            Span::styled(format!("{}", summary.char_stats.corrected), value_style),
+        ]),
+        Line::from(vec![
+            Span::styled("Characters: ", label_style),
+            Span::styled(summary.char_stats.to_string(), value_style),
         ]),
         Line::from(vec![
             Span::styled("WPM Range: ", label_style),
@@ -236,6 +282,23 @@ fn render_details_section(
         ]),
     ];
 
+    if let Some(quote) = &app.lexicon.quote {
+        lines.push(Line::from(vec![
+            Span::styled("Quote: ", label_style),
+            Span::styled(quote.source.clone(), value_style),
+        ]));
+    }
+
+    if let Some(snippet) = &app.lexicon.snippet {
+        lines.push(Line::from(vec![
+            Span::styled("Snippet: ", label_style),
+            Span::styled(
+                format!("{} ({})", snippet.name, snippet.language),
+                value_style,
+            ),
+        ]));
+    }
+
     let block = Block::bordered()
         .title("Details")
         .border_style(Style::default().fg(theme.fg()).add_modifier(Modifier::DIM))
@@ -1,5 +1,6 @@
 use crate::{
     app::App,
+    config::Mode,
     theme::Theme,
     tui::helpers::{calculate_horizontal_padding, center_lines_vertically, max_line_width},
 };
@@ -23,12 +24,22 @@ pub fn render(frame: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
     let accent_style = Style::default().fg(theme.accent());
     let error_style = Style::default().fg(theme.error());
 
-    let mode_info = if app.config.current_mode().is_time_mode() {
-        format!("Time({}s)", app.config.current_mode().value())
-    } else {
-        format!("Words({})", app.config.current_mode().value())
+    let mode_info = match app.tracker.mode {
+        Mode::Time(secs) => format!("Time({secs}s)"),
+        Mode::Words(count) => format!("Words({count})"),
+        Mode::Quote(length) => format!("Quote({})", length.label()),
+        Mode::Zen => "Zen".to_string(),
+        Mode::Code => "Code".to_string(),
+        Mode::Practice(repetitions) => format!("Practice({repetitions}x)"),
     };
 
+    let quote_source = app.lexicon.quote.as_ref().map(|q| q.source.clone());
+    let snippet_name = app
+        .lexicon
+        .snippet
+        .as_ref()
+        .map(|s| format!("{} ({})", s.name, s.language));
+
     let stats: Vec<Line> = [
         (
             "WPM",
@@ -42,6 +53,23 @@ pub fn render(frame: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
             "Accuracy",
             Span::styled(format!("{:.1}%", summary.accuracy * 100.0), value_style),
         ),
+        (
+            "Key Accuracy",
+            Span::styled(
+                format!("{:.1}%", summary.keystroke_accuracy * 100.0),
+                value_style,
+            ),
+        ),
+        (
+            "Characters",
+            Span::styled(
+                format!(
+                    "{} ({} corrected)",
+                    summary.char_stats, summary.char_stats.corrected
+                ),
+                value_style,
+            ),
+        ),
         (
             "Language",
             Span::styled(app.config.current_language(), value_style),
@@ -49,6 +77,8 @@ pub fn render(frame: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
         ("Mode", Span::styled(mode_info, value_style)),
     ]
     .into_iter()
+    .chain(quote_source.map(|source| ("Quote", Span::styled(source, value_style))))
+    .chain(snippet_name.map(|name| ("Snippet", Span::styled(name, value_style))))
     .map(|(label, value)| Line::from(vec![Span::styled(format!("{label}: "), label_style), value]))
     .chain(std::iter::once(Line::from("")))
     .collect();
@@ -1,24 +1,89 @@
-use crate::{app::App, theme::Theme, tui::layout::ResultsLayout, variants::ResultsVariant};
-use ratatui::Frame;
+use crate::{
+    app::App,
+    key_stats::{KeyStats, NgramStats},
+    theme::Theme,
+    tui::{
+        components::{heatmap, typing_cache},
+        layout::ResultsLayout,
+    },
+    variants::ResultsVariant,
+};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::Paragraph,
+};
 
 pub mod bottom_bar;
 pub mod graph;
 pub mod minimal;
 pub mod neofetch;
 
+/// At most `1 / N` of the results area is given to the annotated text
+const MAX_ANNOTATED_TEXT_SHARE: u16 = 3;
+
 pub struct Results;
 
 impl Results {
     pub fn render(frame: &mut Frame, app: &mut App, theme: &Theme, layout: ResultsLayout) {
         let current_variant = app.config.current_results_variant();
-        let area = layout.results_area;
+        let mut area = layout.results_area;
+
+        // failed tests, and saved tests left out of the rankings, get a banner on top of
+        // whatever variant is in use
+        let banner = if let Some(reason) = app.tracker.failure {
+            Some((theme.error(), format!("Test failed: {reason}")))
+        } else {
+            let validity = app.tracker.validity();
+            (app.practice.is_none() && !validity.is_valid()).then(|| {
+                let message = format!("Test invalid ({validity}), it won't be ranked");
+                (theme.warning(), message)
+            })
+        };
+        if let Some((color, message)) = banner {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(2), Constraint::Min(0)])
+                .split(area);
+            render_banner(frame, color, &message, chunks[0]);
+            area = chunks[1];
+        }
+
+        // blind and memory tests kept the mistakes hidden, they are revealed below the results
+        if app.config.text_visibility().hides_text() {
+            let lines = typing_cache::annotated_lines(&app.tracker, theme, area.width);
+            let height = (lines.len() as u16).min(area.height / MAX_ANNOTATED_TEXT_SHARE);
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(height)])
+                .split(area);
+            frame.render_widget(Paragraph::new(lines), chunks[1]);
+            area = chunks[0];
+        }
 
         match current_variant {
             ResultsVariant::Minimal => minimal::render(frame, app, theme, area),
             ResultsVariant::Graph => graph::render(frame, app, theme, area),
             ResultsVariant::Neofetch => neofetch::render(frame, app, theme, area),
+            ResultsVariant::Heatmap => heatmap::render(
+                frame,
+                theme,
+                &KeyStats::from_tracker(&app.tracker),
+                &NgramStats::from_tracker(&app.tracker),
+                1,
+                area,
+            ),
         }
 
         bottom_bar::render_bar(frame, theme, current_variant, layout.footer_area);
     }
 }
+
+fn render_banner(frame: &mut Frame, color: Color, message: &str, area: Rect) {
+    let style = Style::default().fg(color).add_modifier(Modifier::BOLD);
+    let banner =
+        Paragraph::new(Line::styled(message.to_string(), style)).alignment(Alignment::Center);
+    frame.render_widget(banner, area);
+}
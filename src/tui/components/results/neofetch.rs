@@ -1,5 +1,6 @@
 use crate::{
-    app::App, ascii, common::strings::truncate_to_width, constants::APP_NAME, theme::Theme,
+    app::App, ascii, common::strings::truncate_to_width, config::Mode, constants::APP_NAME,
+    theme::Theme,
 };
 use ratatui::{
     layout::Alignment,
@@ -61,10 +62,13 @@ pub fn render(frame: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
     let value_style = Style::default().fg(theme.fg());
     let dim_style = Style::default().fg(theme.muted());
 
-    let mode_str = if app.config.current_mode().is_time_mode() {
-        format!("Time ({}s)", app.config.current_mode().value())
-    } else {
-        format!("Words ({})", app.config.current_mode().value())
+    let mode_str = match app.tracker.mode {
+        Mode::Time(secs) => format!("Time ({secs}s)"),
+        Mode::Words(count) => format!("Words ({count})"),
+        Mode::Quote(length) => format!("Quote ({})", length.label()),
+        Mode::Zen => "Zen".to_string(),
+        Mode::Code => "Code".to_string(),
+        Mode::Practice(repetitions) => format!("Practice ({repetitions}x)"),
     };
 
     let total_keystrokes = summary.correct_chars + summary.total_errors;
@@ -74,10 +78,13 @@ pub fn render(frame: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
     let raw_wpm_str = format!("{:.0}", summary.raw_wpm());
     let duration_str = format!("{:.1}s", summary.elapsed_time.as_secs_f64());
     let accuracy_str = format!("{:.0}%", summary.accuracy * 100.0);
+    let keystroke_accuracy_str = format!("{:.0}%", summary.keystroke_accuracy * 100.0);
     let consistency_str = format!("{:.0}%", summary.consistency);
     let keystrokes_str = format!("{} ({})", summary.correct_chars, total_keystrokes);
     let correct_str = format!("{}", summary.correct_chars);
     let errors_str = format!("{}", summary.total_errors);
+    let characters_str = summary.char_stats.to_string();
+    let corrected_str = format!("{}", summary.char_stats.corrected);
     let min_wpm = if summary.snapshots.is_empty() {
         summary.wpm
     } else {
@@ -90,7 +97,14 @@ pub fn render(frame: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
     };
     let wpm_range_str = format!("{min_wpm:.0}–{max_wpm:.0}",);
 
-    let stats = vec![
+    let quote_source = app.lexicon.quote.as_ref().map(|q| q.source.clone());
+    let snippet_name = app
+        .lexicon
+        .snippet
+        .as_ref()
+        .map(|s| format!("{} ({})", s.name, s.language));
+
+    let mut stats = vec![
         (format!("{username}@{hostname}"), header_style, value_style),
         ("".to_string(), dim_style, value_style), // separator line
         ("OS".to_string(), label_style, value_style),
@@ -101,14 +115,17 @@ pub fn render(frame: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
         ("Raw WPM".to_string(), label_style, value_style),
         ("Duration".to_string(), label_style, value_style),
         ("Accuracy".to_string(), label_style, value_style),
+        ("Key Accuracy".to_string(), label_style, value_style),
         ("Consistency".to_string(), label_style, value_style),
         ("Keystrokes".to_string(), label_style, value_style),
         ("Correct".to_string(), label_style, value_style),
         ("Errors".to_string(), label_style, value_style),
+        ("Characters".to_string(), label_style, value_style),
+        ("Corrected".to_string(), label_style, value_style),
         ("WPM Range".to_string(), label_style, value_style),
     ];
 
-    let values = vec![
+    let mut values = vec![
         "",
         "",
         APP_NAME,
@@ -119,13 +136,26 @@ pub fn render(frame: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
         &raw_wpm_str,
         &duration_str,
         &accuracy_str,
+        &keystroke_accuracy_str,
         &consistency_str,
         &keystrokes_str,
         &correct_str,
         &errors_str,
+        &characters_str,
+        &corrected_str,
         &wpm_range_str,
     ];
 
+    if let Some(source) = quote_source.as_deref() {
+        stats.push(("Quote".to_string(), label_style, value_style));
+        values.push(source);
+    }
+
+    if let Some(name) = snippet_name.as_deref() {
+        stats.push(("Snippet".to_string(), label_style, value_style));
+        values.push(name);
+    }
+
     let stats_width = stats
         .iter()
         .enumerate()
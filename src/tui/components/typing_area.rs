@@ -2,7 +2,10 @@ use crate::{
     app::App,
     theme::Theme,
     tui::{
-        helpers::{calculate_padding, resolve_visible_window, set_cursor_position},
+        helpers::{
+            ViewportWindow, calculate_padding, resolve_visible_window, set_cursor_position,
+            typing_area_position,
+        },
         layout::AppLayout,
     },
 };
@@ -22,13 +25,14 @@ pub fn render_typing_area(frame: &mut Frame, app: &mut App, theme: &Theme, layou
     } else {
         create_language_line(app, theme)
     };
-    let line_count = app.config.current_line_count();
+    let flow = app.config.text_flow();
+    let line_count = flow.visible_lines();
     app.typing_cache.ensure(
         &app.tracker,
         theme,
         layout.center_area.width,
-        line_count,
-        app.typing_revision,
+        flow,
+        app.config.text_visibility(),
     );
     let viewport = resolve_visible_window(
         app.typing_cache.cursor_line(),
@@ -49,6 +53,9 @@ pub fn render_typing_area(frame: &mut Frame, app: &mut App, theme: &Theme, layou
 
     frame.render_widget(paragraph, layout.center_area);
 
+    render_pace_caret(frame, app, theme, layout, padding, viewport);
+    render_ghost_caret(frame, app, theme, layout, padding, viewport);
+
     set_cursor_position(
         frame,
         app,
@@ -59,6 +66,78 @@ pub fn render_typing_area(frame: &mut Frame, app: &mut App, theme: &Theme, layou
     );
 }
 
+/// Highlights the cell the pace caret is on. The position is resolved every frame against the
+/// cached lines, the pace caret never invalidates the render cache
+fn render_pace_caret(
+    frame: &mut Frame,
+    app: &App,
+    theme: &Theme,
+    layout: &AppLayout,
+    pad_size: usize,
+    viewport: ViewportWindow,
+) {
+    let Some(wpm) = app.pace_wpm else {
+        return;
+    };
+    if !app.tracker.in_progress() {
+        return;
+    }
+
+    let pos = app.tracker.pace_position(wpm);
+    let style = Style::default()
+        .fg(theme.cursor_text())
+        .bg(theme.cursor())
+        .add_modifier(Modifier::DIM);
+    highlight_token(frame, app, layout, pad_size, viewport, pos, style);
+}
+
+/// Highlights the cell the raced ghost is on, same as the pace caret but in its own color
+fn render_ghost_caret(
+    frame: &mut Frame,
+    app: &App,
+    theme: &Theme,
+    layout: &AppLayout,
+    pad_size: usize,
+    viewport: ViewportWindow,
+) {
+    let Some(ghost) = &app.ghost else {
+        return;
+    };
+    if !app.tracker.in_progress() {
+        return;
+    }
+
+    let pos = app.tracker.token_index_of(ghost.position());
+    let style = Style::default().fg(theme.bg()).bg(theme.info());
+    highlight_token(frame, app, layout, pad_size, viewport, pos, style);
+}
+
+fn highlight_token(
+    frame: &mut Frame,
+    app: &App,
+    layout: &AppLayout,
+    pad_size: usize,
+    viewport: ViewportWindow,
+    pos: usize,
+    style: Style,
+) {
+    let Some((line, x)) = app.typing_cache.position_of(pos) else {
+        return;
+    };
+    if line < viewport.start || line >= viewport.end {
+        return;
+    }
+
+    let position = typing_area_position(layout, pad_size, x, line - viewport.start);
+    if !layout.center_area.contains(position) {
+        return;
+    }
+
+    if let Some(cell) = frame.buffer_mut().cell_mut(position) {
+        cell.set_style(style);
+    }
+}
+
 fn create_language_line(app: &mut App, theme: &Theme) -> Line<'static> {
     let language_span = Span::styled(
         app.config.current_language(),
@@ -75,10 +154,14 @@ fn create_tracker_line(app: &mut App, theme: &Theme) -> Line<'static> {
             let secs_left = (total_secs as i64 - elapsed_secs as i64).max(0);
             format!("{secs_left}")
         }
-        crate::config::Mode::Words(_) => {
+        crate::config::Mode::Words(_)
+        | crate::config::Mode::Quote(_)
+        | crate::config::Mode::Code
+        | crate::config::Mode::Practice(_) => {
             let summary = app.tracker.summary();
             format!("{}/{}", summary.completed_words, summary.total_words)
         }
+        crate::config::Mode::Zen => format!("{}", app.tracker.words.len()),
     };
     let mut spans = vec![Span::styled(
         mode_progress,
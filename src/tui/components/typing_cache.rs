@@ -1,23 +1,46 @@
-use crate::{theme::Theme, tracker::Tracker};
+use crate::{
+    config::{TextFlow, TextVisibility},
+    events::{TrackerEvent, TrackerObserver},
+    theme::Theme,
+    tracker::{Token, Tracker},
+    tui::helpers::resolve_visible_window,
+};
 use ratatui::{
     style::{Modifier, Style},
     text::{Line, Span},
 };
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Placeholder for each cell of the text memory mode hides
+const MEMORY_MASK: &str = "_";
 
 #[derive(Debug, Default, Clone)]
 pub struct TypingRenderCache {
-    revision: u64,
+    /// Whether `lines` still match the tracker. Cleared by the tracker events that change the text
+    fresh: bool,
     width: u16,
-    line_count: u8,
+    flow: TextFlow,
     theme_revision: u64,
+    visibility: TextVisibility,
     lines: Vec<Line<'static>>,
+    /// Position of the first token in `lines`. The tape only holds the tokens on screen
+    first_pos: usize,
     cursor_line: usize,
     cursor_x: u16,
+    /// When each line was first on screen during the test, by line index. The tape only has
+    /// one line, so there it is by token position instead. Used by memory mode
+    shown_at: HashMap<usize, Instant>,
+    /// When the next line shown in memory mode gets masked, the lines go stale then
+    next_mask_at: Option<Instant>,
 }
 
 impl TypingRenderCache {
     pub fn invalidate(&mut self) {
-        self.revision = u64::MAX;
+        self.fresh = false;
     }
 
     pub fn ensure(
@@ -25,26 +48,93 @@ impl TypingRenderCache {
         tracker: &Tracker,
         theme: &Theme,
         width: u16,
-        line_count: u8,
-        revision: u64,
+        flow: TextFlow,
+        visibility: TextVisibility,
     ) {
-        let unchanged = self.revision == revision
+        let now = Instant::now();
+        let unchanged = self.fresh
             && self.width == width
-            && self.line_count == line_count
-            && self.theme_revision == theme.revision();
+            && self.flow == flow
+            && self.theme_revision == theme.revision()
+            && self.visibility == visibility
+            && self.next_mask_at.is_none_or(|mask_at| now < mask_at);
         if unchanged {
             return;
         }
+        // what memory mode times depends on the flow
+        if self.flow != flow {
+            self.shown_at.clear();
+            self.flow = flow;
+        }
 
-        let (lines, cursor_line, cursor_x) = build_target_text_lines(tracker, theme, width);
+        let (mut lines, first_pos, cursor_line, cursor_x) = match flow {
+            TextFlow::Lines(_) => {
+                let (lines, cursor_line, cursor_x) =
+                    build_target_text_lines(tracker, theme, width, visibility.blind);
+                (lines, 0, cursor_line, cursor_x)
+            }
+            TextFlow::Tape(column) => {
+                let (line, first_pos, cursor_x) =
+                    build_tape_line(tracker, theme, width, column, visibility.blind);
+                (vec![line], first_pos, 0, cursor_x)
+            }
+        };
+        self.next_mask_at = None;
+        if let Some(memory) = visibility.memory {
+            let line_count = flow.visible_lines() as usize;
+            let viewport = resolve_visible_window(cursor_line, lines.len(), line_count);
+            if tracker.in_progress() {
+                let shown = match flow {
+                    TextFlow::Lines(_) => viewport.start..viewport.end,
+                    TextFlow::Tape(_) => first_pos..first_pos + lines[0].spans.len(),
+                };
+                for key in shown {
+                    self.shown_at.entry(key).or_insert(now);
+                }
+            }
+            self.mask_memorized_lines(&mut lines, first_pos, tracker.current_pos, memory, now);
+        }
 
         self.lines = lines;
+        self.first_pos = first_pos;
         self.cursor_line = cursor_line;
         self.cursor_x = cursor_x;
-        self.revision = revision;
+        self.fresh = true;
         self.width = width;
-        self.line_count = line_count;
         self.theme_revision = theme.revision();
+        self.visibility = visibility;
+    }
+
+    /// Masks what is left to type in the lines (tokens on the tape) that have been on screen for
+    /// longer than `memory`, and keeps track of when the next one is due
+    fn mask_memorized_lines(
+        &mut self,
+        lines: &mut [Line<'static>],
+        first_pos: usize,
+        current_pos: usize,
+        memory: Duration,
+        now: Instant,
+    ) {
+        let is_tape = matches!(self.flow, TextFlow::Tape(_));
+        let mut line_start = first_pos;
+        for (idx, line) in lines.iter_mut().enumerate() {
+            let first_pos = line_start;
+            line_start += line.spans.len();
+            for (pos, span) in (first_pos..).zip(line.spans.iter_mut()) {
+                let key = if is_tape { pos } else { idx };
+                let Some(&shown_at) = self.shown_at.get(&key) else {
+                    continue;
+                };
+
+                let mask_at = shown_at + memory;
+                if now < mask_at {
+                    self.next_mask_at =
+                        Some(self.next_mask_at.map_or(mask_at, |at| at.min(mask_at)));
+                } else if pos >= current_pos && span.content != " " {
+                    span.content = MEMORY_MASK.repeat(span.width()).into();
+                }
+            }
+        }
     }
 
     pub fn lines(&self) -> &[Line<'static>] {
@@ -58,61 +148,68 @@ impl TypingRenderCache {
     pub fn cursor_x(&self) -> u16 {
         self.cursor_x
     }
+
+    /// Line and column of an arbitrary token position in the cached lines, `None` if the tape
+    /// scrolled past it. Used by carets that move on their own (i.e the pace caret) so they don't
+    /// need to invalidate the cache
+    pub fn position_of(&self, pos: usize) -> Option<(usize, u16)> {
+        let pos = pos.checked_sub(self.first_pos)?;
+        let cached: usize = self.lines.iter().map(|line| line.spans.len()).sum();
+        (pos <= cached).then(|| resolve_cursor(&self.lines, pos))
+    }
+}
+
+impl TrackerObserver for TypingRenderCache {
+    fn on_event(&mut self, event: &TrackerEvent, _tracker: &Tracker) {
+        if matches!(event, TrackerEvent::Reset) {
+            self.shown_at.clear();
+        }
+        if event.changes_text() {
+            self.invalidate();
+        }
+    }
+}
+
+/// The typed text with every mistake marked, up to the line the test ended on. Revealed on the
+/// results screen when blind or memory mode kept it hidden
+pub fn annotated_lines(tracker: &Tracker, theme: &Theme, width: u16) -> Vec<Line<'static>> {
+    let (mut lines, cursor_line, _) = build_target_text_lines(tracker, theme, width, false);
+    lines.truncate(cursor_line + 1);
+    lines
 }
 
 fn build_target_text_lines(
     state: &Tracker,
     theme: &Theme,
     max_width: u16,
+    blind: bool,
 ) -> (Vec<Line<'static>>, usize, u16) {
     let mut spans = Vec::with_capacity(state.tokens.len());
     let mut word_idx = 0;
 
     for (i, token) in state.tokens.iter().enumerate() {
-        if token.target == ' ' {
+        if starts_word(state, i) {
             word_idx += 1;
         }
-
-        let is_past_wrong_word = word_idx < state.current_word_idx && state.is_word_wrong(word_idx);
-        let fg_color = if token.is_skipped {
-            theme.fg()
-        } else if i < state.current_pos {
-            if token.is_wrong {
-                theme.error()
-            } else {
-                theme.success()
-            }
-        } else {
-            theme.fg()
-        };
-
-        let mut style = Style::default().fg(fg_color);
-        if token.is_skipped || i >= state.current_pos {
-            style = style.add_modifier(Modifier::DIM);
-        }
-
-        if token.is_extra_token() {
-            style = style.add_modifier(Modifier::DIM);
-        }
-
-        if is_past_wrong_word && token.target != ' ' {
-            style = style
-                .add_modifier(Modifier::UNDERLINED)
-                .underline_color(theme.error());
-        }
-
-        spans.push(Span::styled(token.target.to_string(), style));
+        let style = token_style(state, theme, i, word_idx, blind);
+        spans.push((
+            Span::styled(display_char(token), style),
+            token.target == '\n',
+        ));
     }
 
     let mut lines = Vec::new();
     let mut current_line: Vec<Span<'static>> = Vec::new();
     let mut current_width: u16 = 0;
 
-    for span in spans {
-        let span_width = span.content.len() as u16;
+    for (span, is_line_break) in spans {
+        // one span per token, wide graphemes (CJK, emoji) take two cells
+        let span_width = span.width() as u16;
         if current_width + span_width > max_width {
+            // never wrap inside the preserved indentation of a line
+            let indent_len = current_line.iter().take_while(|s| s.content == " ").count();
             let mut break_index = current_line.len();
-            for (i, s) in current_line.iter().enumerate().rev() {
+            for (i, s) in current_line.iter().enumerate().skip(indent_len).rev() {
                 if s.content == " " {
                     break_index = i + 1;
                     break;
@@ -123,7 +220,7 @@ fn build_target_text_lines(
                 let next_line = current_line.split_off(break_index);
                 lines.push(Line::from(current_line));
                 current_line = next_line;
-                current_width = current_line.iter().map(|s| s.content.len() as u16).sum();
+                current_width = current_line.iter().map(|s| s.width() as u16).sum();
             } else {
                 lines.push(Line::from(current_line));
                 current_line = Vec::new();
@@ -136,6 +233,12 @@ fn build_target_text_lines(
             current_line.push(span);
             current_width += span_width;
         }
+
+        // hard line breaks always end the current line
+        if is_line_break {
+            lines.push(Line::from(std::mem::take(&mut current_line)));
+            current_width = 0;
+        }
     }
 
     if !current_line.is_empty() {
@@ -146,20 +249,167 @@ fn build_target_text_lines(
     (lines, cursor_line, cursor_x)
 }
 
+/// Tape mode: the text is a single line and the caret stays at `column` while the text slides
+/// under it. Only the tokens that fit on screen are styled, so every keystroke costs a screen
+/// width of work no matter how long the text is. Returns the line, the position of its first
+/// token and the column of the caret
+fn build_tape_line(
+    state: &Tracker,
+    theme: &Theme,
+    max_width: u16,
+    column: u16,
+    blind: bool,
+) -> (Line<'static>, usize, u16) {
+    let column = column.min(max_width.saturating_sub(1));
+    let current_pos = state.current_pos.min(state.tokens.len());
+
+    // typed text fills the columns left of the caret, whatever doesn't fit slid off already
+    let mut first_pos = current_pos;
+    let mut cursor_x: u16 = 0;
+    while first_pos > 0 {
+        let width = display_char(&state.tokens[first_pos - 1]).width() as u16;
+        if cursor_x + width > column {
+            break;
+        }
+        cursor_x += width;
+        first_pos -= 1;
+    }
+
+    let mut spans = Vec::new();
+    let mut line_width: u16 = 0;
+    let mut word_idx = state.word_index_at(first_pos);
+    for (i, token) in state.tokens.iter().enumerate().skip(first_pos) {
+        if i > first_pos && starts_word(state, i) {
+            word_idx += 1;
+        }
+        let span = Span::styled(
+            display_char(token),
+            token_style(state, theme, i, word_idx, blind),
+        );
+        line_width += span.width() as u16;
+        if line_width > max_width {
+            break;
+        }
+        spans.push(span);
+    }
+
+    (Line::from(spans), first_pos, cursor_x)
+}
+
+/// A word starts after its separators, paragraph breaks only count once
+fn starts_word(state: &Tracker, pos: usize) -> bool {
+    pos > 0 && !state.tokens[pos].is_separator() && state.tokens[pos - 1].is_separator()
+}
+
+fn token_style(state: &Tracker, theme: &Theme, pos: usize, word_idx: usize, blind: bool) -> Style {
+    let token = &state.tokens[pos];
+    // zen tests have no target, the buffer is whatever was typed and it's never wrong
+    let is_zen = state.mode.is_zen_mode();
+    // blind tests give nothing away, skipped and extra characters included
+    let is_past_wrong_word =
+        !blind && word_idx < state.current_word_idx && state.is_word_wrong(word_idx);
+    let fg_color = if token.is_skipped || is_zen || blind {
+        theme.fg()
+    } else if pos < state.current_pos {
+        if token.is_wrong {
+            theme.error()
+        } else {
+            theme.success()
+        }
+    } else {
+        theme.fg()
+    };
+
+    let mut style = Style::default().fg(fg_color);
+    if (token.is_skipped && !blind) || pos >= state.current_pos {
+        style = style.add_modifier(Modifier::DIM);
+    }
+
+    if token.is_extra_token() && !blind {
+        style = style.add_modifier(Modifier::DIM);
+    }
+
+    if is_past_wrong_word && !token.is_separator() {
+        style = style
+            .add_modifier(Modifier::UNDERLINED)
+            .underline_color(theme.error());
+    }
+    style
+}
+
+/// Line breaks take a single cell at the end of their line so the cursor can sit on them
+fn display_char(token: &Token) -> String {
+    if token.target == '\n' {
+        " ".to_string()
+    } else {
+        token.target.to_string()
+    }
+}
+
+/// Every span is a token, so positions count spans while columns add up their widths
 fn resolve_cursor(lines: &[Line<'static>], current_pos: usize) -> (usize, u16) {
     let mut cumulative = 0;
     let mut cursor_line = 0;
     let mut cursor_x = 0;
 
     for (i, line) in lines.iter().enumerate() {
-        let line_len = line.spans.iter().map(|s| s.content.len()).sum::<usize>();
+        let line_len = line.spans.len();
         if current_pos < cumulative + line_len {
-            cursor_line = i;
-            cursor_x = (current_pos - cumulative) as u16;
-            break;
+            let x: usize = line.spans[..current_pos - cumulative]
+                .iter()
+                .map(Span::width)
+                .sum();
+            return (i, x as u16);
         }
         cumulative += line_len;
+        // the cursor sits right after the last token, i.e a growing zen buffer
+        cursor_line = i;
+        cursor_x = line.width() as u16;
     }
 
     (cursor_line, cursor_x)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Mode;
+
+    #[test]
+    fn test_tape_memory_masks_tokens_by_time_on_screen() {
+        let theme = Theme::default();
+        let memory = Duration::from_secs(1);
+        let visibility = TextVisibility {
+            blind: false,
+            memory: Some(memory),
+        };
+        let flow = TextFlow::Tape(2);
+        let mut tracker = Tracker::new("aaaa bbbb cccc dddd".to_string(), Mode::with_words(4));
+        let mut cache = TypingRenderCache::default();
+
+        tracker.type_char('a').unwrap();
+        cache.ensure(&tracker, &theme, 10, flow, visibility);
+        assert_eq!(cache.shown_at.len(), 10);
+        let long_ago = Instant::now() - memory * 2;
+        cache.shown_at.values_mut().for_each(|at| *at = long_ago);
+
+        for c in "aaa ".chars() {
+            tracker.type_char(c).unwrap();
+        }
+        cache.invalidate();
+        cache.ensure(&tracker, &theme, 10, flow, visibility);
+
+        // the tape scrolled 3 tokens, only the ones that were already on screen are masked
+        let span_at = |pos: usize| {
+            cache.lines()[0].spans[pos - cache.first_pos]
+                .content
+                .clone()
+        };
+        assert_eq!(cache.first_pos, 3);
+        assert_eq!(span_at(5), MEMORY_MASK);
+        assert_eq!(span_at(9), " ");
+        assert_eq!(span_at(10), "c");
+        assert_eq!(span_at(12), "c");
+        assert!(cache.next_mask_at.is_some());
+    }
+}
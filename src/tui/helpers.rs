@@ -30,6 +30,22 @@ pub fn resolve_visible_window(
     }
 }
 
+/// Rows of the typing area above the text, the language line and the gap below it
+const TYPING_AREA_HEADER_ROWS: u16 = 2;
+
+/// Screen position of a column of a visible line of the text in the typing area
+pub fn typing_area_position(
+    layout: &AppLayout,
+    pad_size: usize,
+    x: u16,
+    visible_line: usize,
+) -> Position {
+    Position {
+        x: layout.center_area.x + x,
+        y: layout.center_area.y + pad_size as u16 + visible_line as u16 + TYPING_AREA_HEADER_ROWS,
+    }
+}
+
 pub fn set_cursor_position(
     frame: &mut Frame,
     app: &App,
@@ -42,12 +58,12 @@ pub fn set_cursor_position(
         // don't even bother calculating stuff if we should not set the cursor position
         return;
     }
-    let header_offset = 2;
-
-    frame.set_cursor_position(Position {
-        x: layout.center_area.x + cursor_x,
-        y: layout.center_area.y + pad_size as u16 + visible_cursor_y as u16 + header_offset as u16,
-    });
+    frame.set_cursor_position(typing_area_position(
+        layout,
+        pad_size,
+        cursor_x,
+        visible_cursor_y,
+    ));
 }
 
 /// Checks if we should render the cursor in the typing area or not
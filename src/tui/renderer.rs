@@ -125,6 +125,14 @@ fn render_typing_screen(frame: &mut Frame, app: &mut App, theme: &Theme, layout:
         frame.render_widget(title, rect);
     }
 
+    // the ghost race score stays up while typing
+    if app.ghost.is_some()
+        && let Some(rect) = layout.mode_bar_area
+    {
+        let mode_line = mode_bar::create_mode_line(app, theme, rect.height, rect.width);
+        frame.render_widget(mode_line, rect);
+    }
+
     // typing area
     if !app.menu.is_open() {
         typing_area::render_typing_area(frame, app, theme, &layout);
@@ -209,6 +209,8 @@ pub enum ResultsVariant {
     /// Neofetch-style display.
     #[default]
     Neofetch,
+    /// On-screen keyboard coloured by the error rate of each key.
+    Heatmap,
 }
 
 impl std::str::FromStr for ResultsVariant {
@@ -219,13 +221,14 @@ impl std::str::FromStr for ResultsVariant {
             "graph" => Ok(Self::Graph),
             "neofetch" => Ok(Self::Neofetch),
             "minimal" => Ok(Self::Minimal),
+            "heatmap" => Ok(Self::Heatmap),
             _ => Err(Error),
         }
     }
 }
 
 impl ResultsVariant {
-    pub const ALL: &'static [Self] = &[Self::Graph, Self::Neofetch, Self::Minimal];
+    pub const ALL: &'static [Self] = &[Self::Graph, Self::Neofetch, Self::Minimal, Self::Heatmap];
     pub const NAME: &'static str = "results";
 
     pub fn all() -> &'static [Self] {
@@ -241,6 +244,7 @@ impl ResultsVariant {
             Self::Graph => "graph",
             Self::Neofetch => "neofetch",
             Self::Minimal => "minimal",
+            Self::Heatmap => "heatmap",
         }
     }
 
@@ -249,6 +253,7 @@ impl ResultsVariant {
             Self::Graph => "Graph",
             Self::Neofetch => "Neofetch",
             Self::Minimal => "Minimal",
+            Self::Heatmap => "Heatmap",
         }
     }
 }
@@ -299,6 +304,6 @@ mod tests {
     fn test_all_variants() {
         assert_eq!(CursorVariant::all().len(), 6);
         assert_eq!(PickerVariant::all().len(), 4);
-        assert_eq!(ResultsVariant::all().len(), 3);
+        assert_eq!(ResultsVariant::all().len(), 4);
     }
 }